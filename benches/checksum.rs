@@ -0,0 +1,56 @@
+//! Measures the throughput of the checksum against the `adler32` crate it replaced.
+//!
+//! Run with `cargo bench`.
+
+use std::hint::black_box;
+use std::time::Duration;
+use std::time::Instant;
+
+use adler32::RollingAdler32;
+use dex_checksum_tools::checksum_of;
+
+/// The file sizes measured, from a small DEX file to a large merged one.
+const SIZES: [usize; 4] = [64 << 10, 1 << 20, 16 << 20, 64 << 20];
+
+/// How long each measurement runs for, at least.
+const TARGET: Duration = Duration::from_millis(500);
+
+/// Runs `f` repeatedly for at least `TARGET`, and returns the throughput in MB/s.
+fn throughput<F: FnMut()>(len: usize, mut f: F) -> f64 {
+	f();
+	let start = Instant::now();
+	let mut iterations = 0;
+	while start.elapsed() < TARGET {
+		f();
+		iterations += 1;
+	}
+	(len * iterations) as f64 / start.elapsed().as_secs_f64() / 1e6
+}
+
+fn main() {
+	println!(
+		"{:>10} {:>16} {:>16}",
+		"size", "checksum_of", "adler32 crate"
+	);
+	for len in SIZES {
+		let bytes: Vec<u8> = (0..len).map(|i| (i * 31 + i / 7) as u8).collect();
+		assert_eq!(
+			checksum_of(&bytes),
+			RollingAdler32::from_buffer(&bytes[12..])
+				.hash()
+				.to_le_bytes()
+		);
+		let ours = throughput(len, || {
+			black_box(checksum_of(black_box(&bytes)));
+		});
+		let reference = throughput(len, || {
+			black_box(RollingAdler32::from_buffer(black_box(&bytes[12..])).hash());
+		});
+		println!(
+			"{:>9}K {:>11.0} MB/s {:>11.0} MB/s",
+			len >> 10,
+			ours,
+			reference
+		);
+	}
+}
@@ -0,0 +1,27 @@
+//! Feeds arbitrary bytes through header parsing and every fallible checksum path,
+//! none of which may panic.
+
+#![no_main]
+
+use dex_checksum_tools::{correct_checksum_in_place, correct_signature_in_place, Dex};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+	let mut dex = Dex::from_slice(data);
+	let _ = dex.header();
+	let _ = dex.validate_sections();
+	let _ = dex.map_list();
+	if let Ok(strings) = dex.strings() {
+		strings.for_each(drop);
+	}
+	let _ = dex.try_current_checksum();
+	let _ = dex.try_expect_checksum();
+	let _ = dex.try_current_signature();
+	let _ = dex.try_expect_signature();
+	let _ = dex.try_correct_all();
+	let _ = Dex::try_from_bytes(data.to_vec());
+
+	let mut buf = data.to_vec();
+	let _ = correct_signature_in_place(&mut buf);
+	let _ = correct_checksum_in_place(&mut buf);
+});
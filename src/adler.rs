@@ -0,0 +1,89 @@
+//! A chunked Adler-32, laid out so the compiler can vectorize its inner loop.
+
+/// The modulus of the Adler-32 sums.
+const MOD: u32 = 65521;
+
+/// The largest number of bytes each sum can take before it has to be reduced to avoid
+/// overflowing a `u32`, as in zlib.
+const NMAX: usize = 5552;
+
+/// The number of lanes summed side by side. Byte `i` of each group of `LANES` goes to lane `i`.
+const LANES: usize = 16;
+
+/// The number of bytes summed between two reductions, `NMAX` per lane.
+const CHUNK_SIZE: usize = NMAX * LANES;
+
+/// An incremental Adler-32.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Adler32 {
+	a: u32,
+	b: u32,
+}
+
+impl Adler32 {
+	/// Creates the Adler-32 of empty input.
+	pub(crate) fn new() -> Adler32 {
+		Adler32 { a: 1, b: 0 }
+	}
+
+	/// Feeds `bytes` into the sums.
+	///
+	/// Each lane keeps its own running sums of every `LANES`th byte, which have no dependency
+	/// on each other, and they are folded back into `a` and `b` once `bytes` is consumed.
+	pub(crate) fn update(&mut self, bytes: &[u8]) {
+		let (mut a, mut b) = (self.a, self.b);
+		let mut a_lanes = [0u32; LANES];
+		let mut b_lanes = [0u32; LANES];
+		let (grouped, tail) = bytes.split_at(bytes.len() - bytes.len() % LANES);
+		for chunk in grouped.chunks(CHUNK_SIZE) {
+			for group in chunk.chunks_exact(LANES) {
+				for lane in 0..LANES {
+					a_lanes[lane] += u32::from(group[lane]);
+					b_lanes[lane] += a_lanes[lane];
+				}
+			}
+			// Every byte of the chunk adds the `a` it started from to `b`, which can overflow a `u32`.
+			b = ((b as u64 + chunk.len() as u64 * a as u64) % MOD as u64) as u32;
+			for lane in 0..LANES {
+				a_lanes[lane] %= MOD;
+				b_lanes[lane] %= MOD;
+			}
+		}
+		// Lane `i` saw its bytes `LANES` positions apart, each `i` positions too early, so its
+		// `b` is scaled by `LANES` and each of its bytes is subtracted `i` times.
+		for lane in 0..LANES {
+			a += a_lanes[lane];
+			b += LANES as u32 * b_lanes[lane] + (MOD - a_lanes[lane]) * lane as u32;
+		}
+		for &byte in tail {
+			a += u32::from(byte);
+			b += a;
+		}
+		self.a = a % MOD;
+		self.b = b % MOD;
+	}
+
+	/// Returns the checksum of everything fed so far.
+	pub(crate) fn hash(&self) -> u32 {
+		self.b << 16 | self.a
+	}
+}
+
+/// Computes the Adler-32 of `bytes`.
+pub(crate) fn adler32(bytes: &[u8]) -> u32 {
+	let mut adler = Adler32::new();
+	adler.update(bytes);
+	adler.hash()
+}
+
+/// Combines the Adler-32 of two buffers into the Adler-32 of their concatenation,
+/// given the length of the second buffer.
+pub(crate) fn combine(first: u32, second: u32, second_len: usize) -> u32 {
+	let rem = (second_len % MOD as usize) as u64;
+	let (a1, b1) = ((first & 0xffff) as u64, (first >> 16) as u64);
+	let (a2, b2) = ((second & 0xffff) as u64, (second >> 16) as u64);
+	let m = MOD as u64;
+	let a = (a1 + a2 + m - 1) % m;
+	let b = (rem * a1 + b1 + b2 + m - rem) % m;
+	(b << 16 | a) as u32
+}
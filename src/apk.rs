@@ -0,0 +1,263 @@
+//! Reading and correcting the DEX entries inside an APK or an AAB (Android App Bundle).
+
+#[cfg(feature = "std-fs")]
+use std::fs;
+use std::io;
+use std::io::Write;
+#[cfg(feature = "std-fs")]
+use std::path::Path;
+
+use crate::zip::ZipArchive;
+use crate::zip::ZipEntry;
+use crate::Dex;
+use crate::DexError;
+
+/// An `Apk` structure that holds an APK or an AAB (both ZIP archives) in memory.
+///
+/// Entries named `classes.dex`, `classes2.dex`, `classes3.dex`, ... are treated as DEX files
+/// when they are at the root of the archive (the APK layout) or under a module's `dex`
+/// directory, such as `base/dex/classes.dex` (the AAB layout). All other entries, and any
+/// APK Signing Block, are preserved byte for byte when the archive is written back out, and
+/// keep their zipalign alignment.
+///
+/// Note that any APK signature becomes invalid once a DEX entry is changed, so the
+/// written archive has to be re-signed before it can be installed.
+///
+/// # Examples
+///
+/// ```
+/// use dex_checksum_tools::apk::Apk;
+///
+/// # #[cfg(feature = "std-fs")]
+/// if let Ok(mut apk) = Apk::open("/path/to/input.apk") {
+///     for result in apk.correct_all() {
+///         println!("{}: changed {}", result.name, result.changed);
+///     }
+///     match apk.write_to_file("/path/to/output.apk") {
+///         Ok(_) => println!("Successfully wrote to file!"),
+///         Err(e) => println!("Failed to write to file: {}", e),
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Apk {
+	archive: ZipArchive,
+}
+
+impl Apk {
+	/// Opens the APK at the specified path.
+	///
+	/// # Errors
+	/// - [`DexError::Io`] if the file cannot be read.
+	/// - [`DexError::Zip`] if the file is not a valid ZIP archive.
+	#[cfg(feature = "std-fs")]
+	pub fn open<P: AsRef<Path>>(path: P) -> Result<Apk, DexError> {
+		Apk::from_bytes(&fs::read(path)?)
+	}
+
+	/// Parses an APK from the bytes of the archive.
+	///
+	/// # Errors
+	/// Returns [`DexError::Zip`] if the bytes are not a valid ZIP archive.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Apk, DexError> {
+		Ok(Apk {
+			archive: ZipArchive::parse(bytes)?,
+		})
+	}
+
+	/// Returns the names of the DEX entries in the archive, in loading order.
+	///
+	/// Entries are grouped by directory in archive order, then ordered by [`multidex_index`],
+	/// so `classes10.dex` comes after `classes9.dex` whatever their order in the archive.
+	pub fn dex_names(&self) -> Vec<&str> {
+		self
+			.dex_indices()
+			.into_iter()
+			.map(|index| self.archive.entries[index].name.as_str())
+			.collect()
+	}
+
+	/// Returns the indices of the DEX entries in the archive, in the order of [`Apk::dex_names`].
+	fn dex_indices(&self) -> Vec<usize> {
+		let entries = &self.archive.entries;
+		let mut indices: Vec<usize> = (0..entries.len())
+			.filter(|&index| is_dex_entry(&entries[index].name))
+			.collect();
+		let directories: Vec<&str> = indices
+			.iter()
+			.map(|&index| directory(&entries[index].name))
+			.collect();
+		let first_seen = |name: &str| directories.iter().position(|&d| d == directory(name));
+		indices.sort_by_key(|&index| {
+			let name = entries[index].name.as_str();
+			(first_seen(name), multidex_index(name))
+		});
+		indices
+	}
+
+	/// Reads the DEX entry with the given name.
+	///
+	/// # Returns
+	/// - `Ok(Some(dex))` if the entry exists.
+	/// - `Ok(None)` if there is no entry with that name.
+	///
+	/// # Errors
+	/// Returns [`DexError::Zip`] if the entry cannot be decompressed.
+	pub fn dex(&self, name: &str) -> Result<Option<Dex>, DexError> {
+		match self.archive.entries.iter().find(|entry| entry.name == name) {
+			Some(entry) => Ok(Some(Dex::from_bytes(entry.contents()?))),
+			None => Ok(None),
+		}
+	}
+
+	/// Corrects the signature and checksum of every DEX entry in the archive.
+	///
+	/// Corrected entries keep their compression method: stored entries stay stored, and
+	/// deflated entries are deflated again, with fixed Huffman codes only, so they usually
+	/// grow. All other entries are left untouched.
+	///
+	/// Nothing is printed: the outcome for each entry is returned for the caller to present.
+	/// An entry that cannot be corrected does not stop the others from being corrected.
+	///
+	/// # Returns
+	/// One [`ApkDexResult`] per DEX entry, in the order of [`Apk::dex_names`]. Its `error` is
+	/// - [`DexError::Zip`] if the entry cannot be decompressed.
+	/// - [`DexError::TooShort`] if the entry cannot hold a 0x70-byte header.
+	/// - [`DexError::UnsupportedFormat`] if the entry is a CompactDex file or a DEX container.
+	/// - [`DexError::BadMagic`] if the entry is not a DEX file at all.
+	pub fn correct_all(&mut self) -> Vec<ApkDexResult> {
+		self.correct_entries(false)
+	}
+
+	/// Corrects every DEX entry like [`Apk::correct_all`], but stores the corrected entries
+	/// uncompressed.
+	///
+	/// Nothing is re-deflated, so the layout of the archive is disturbed as little as possible.
+	///
+	/// # Returns
+	/// See [`Apk::correct_all`].
+	pub fn correct_all_stored(&mut self) -> Vec<ApkDexResult> {
+		self.correct_entries(true)
+	}
+
+	fn correct_entries(&mut self, store: bool) -> Vec<ApkDexResult> {
+		self
+			.dex_indices()
+			.into_iter()
+			.map(|index| {
+				let entry = &mut self.archive.entries[index];
+				let mut result = ApkDexResult {
+					name: entry.name.clone(),
+					changed: false,
+					old_checksum: None,
+					new_checksum: None,
+					error: None,
+				};
+				if let Err(e) = correct_entry(entry, store, &mut result) {
+					result.error = Some(e);
+				}
+				result
+			})
+			.collect()
+	}
+
+	/// Writes the archive to the given writer.
+	pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+		self.archive.write_to(writer)
+	}
+
+	/// Writes the archive to the specified path, truncating the file if it already exists.
+	#[cfg(feature = "std-fs")]
+	pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+		let mut file = fs::File::create(path)?;
+		self.write_to(&mut file)
+	}
+}
+
+/// The outcome of correcting one DEX entry of an [`Apk`].
+#[derive(Debug)]
+pub struct ApkDexResult {
+	/// The name of the entry, such as `classes2.dex`.
+	pub name: String,
+	/// Whether the entry was corrected and replaced in the archive.
+	pub changed: bool,
+	/// The checksum in the header before the correction, if the header could be read.
+	pub old_checksum: Option<u32>,
+	/// The checksum in the header after the correction, if it succeeded.
+	pub new_checksum: Option<u32>,
+	/// Why the entry could not be corrected, if it could not. The entry is then left untouched.
+	pub error: Option<DexError>,
+}
+
+/// Corrects the signature and checksum of one DEX entry, recording the outcome in `result`.
+fn correct_entry(
+	entry: &mut ZipEntry,
+	store: bool,
+	result: &mut ApkDexResult,
+) -> Result<(), DexError> {
+	let mut dex = Dex::from_bytes(entry.contents()?);
+	dex.validate_minimum_length()?;
+	result.old_checksum = Some(dex.current_checksum_u32());
+	result.changed = dex.try_correct_all()?;
+	result.new_checksum = Some(dex.current_checksum_u32());
+	if result.changed {
+		if store {
+			entry.set_contents_stored(dex.into_bytes());
+		} else {
+			entry.set_contents(dex.into_bytes());
+		}
+	}
+	Ok(())
+}
+
+/// Extracts the multidex index from the name of a DEX file, such as a `classes*.dex` entry.
+///
+/// Android loads `classes.dex` first, then `classes2.dex`, `classes3.dex` and so on, so
+/// sorting by this index orders DEX files the way they are loaded. Any directory in front of
+/// the file name is ignored.
+///
+/// # Returns
+/// - `Some(1)` for `classes.dex`.
+/// - `Some(n)` for `classesN.dex`, where `n` is at least 2 and has no leading zeros.
+/// - `None` for any other name.
+///
+/// # Examples
+///
+/// ```
+/// use dex_checksum_tools::apk::multidex_index;
+///
+/// assert_eq!(multidex_index("classes.dex"), Some(1));
+/// assert_eq!(multidex_index("base/dex/classes12.dex"), Some(12));
+/// assert_eq!(multidex_index("classes1.dex"), None);
+/// assert_eq!(multidex_index("resources.arsc"), None);
+/// ```
+pub fn multidex_index(name: &str) -> Option<u32> {
+	let file_name = name.rsplit('/').next().unwrap_or(name);
+	let index = file_name.strip_prefix("classes")?.strip_suffix(".dex")?;
+	if index.is_empty() {
+		return Some(1);
+	}
+	if index.starts_with('0') || !index.bytes().all(|b| b.is_ascii_digit()) {
+		return None;
+	}
+	index.parse().ok().filter(|&index| index >= 2)
+}
+
+/// Returns the directory part of an entry name, empty at the root of the archive.
+fn directory(name: &str) -> &str {
+	name.rfind('/').map_or("", |end| &name[..end])
+}
+
+/// Checks if an entry name matches `classes*.dex` at the root of the archive (APK),
+/// or `<module>/dex/classes*.dex` (AAB).
+///
+/// Only names with a [`multidex_index`] count: Android never loads `classes1.dex` or
+/// `classes02.dex`, so neither is checked or corrected.
+fn is_dex_entry(name: &str) -> bool {
+	let file_name = match name.split('/').collect::<Vec<_>>().as_slice() {
+		[file_name] => *file_name,
+		[module, "dex", file_name] if !module.is_empty() => *file_name,
+		_ => return false,
+	};
+	multidex_index(file_name).is_some()
+}
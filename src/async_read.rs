@@ -0,0 +1,120 @@
+//! Awaitable reading of DEX files, available with the `async` feature.
+//!
+//! No async runtime is depended upon. [`AsyncRead`] has the shape of `futures::io::AsyncRead`,
+//! so the reader of any runtime can be adapted to it in a few lines.
+
+use std::future::poll_fn;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A source of bytes that can be read without blocking, like `futures::io::AsyncRead`.
+///
+/// This crate does not depend on Tokio, so there is no `from_tokio_file`. Tokio's `AsyncRead`
+/// fills a `ReadBuf` instead of a slice, and a wrapper forwards to it. The example compiles
+/// against a stand-in for the parts of `tokio::io` and `tokio::fs` it uses:
+///
+/// ```
+/// # mod tokio {
+/// #     pub mod io {
+/// #         use std::{io, pin::Pin, task::{Context, Poll}};
+/// #         pub struct ReadBuf<'a>(&'a mut [u8], usize);
+/// #         impl<'a> ReadBuf<'a> {
+/// #             pub fn new(buf: &'a mut [u8]) -> Self { ReadBuf(buf, 0) }
+/// #             pub fn filled(&self) -> &[u8] { &self.0[..self.1] }
+/// #         }
+/// #         pub trait AsyncRead {
+/// #             fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>)
+/// #                 -> Poll<io::Result<()>>;
+/// #         }
+/// #     }
+/// #     pub mod fs {
+/// #         use std::{io, pin::Pin, task::{Context, Poll}};
+/// #         pub struct File;
+/// #         impl File {
+/// #             pub async fn open(_: &str) -> io::Result<File> { Ok(File) }
+/// #         }
+/// #         impl super::io::AsyncRead for File {
+/// #             fn poll_read(self: Pin<&mut Self>, _: &mut Context<'_>, _: &mut super::io::ReadBuf<'_>)
+/// #                 -> Poll<io::Result<()>> { Poll::Ready(Ok(())) }
+/// #         }
+/// #     }
+/// # }
+/// use std::io;
+/// use std::pin::Pin;
+/// use std::task::{ready, Context, Poll};
+///
+/// use dex_checksum_tools::async_read::AsyncRead;
+/// use dex_checksum_tools::Dex;
+///
+/// struct Tokio<R>(R);
+///
+/// impl<R: tokio::io::AsyncRead + Unpin> AsyncRead for Tokio<R> {
+///     fn poll_read(
+///         mut self: Pin<&mut Self>,
+///         cx: &mut Context<'_>,
+///         buf: &mut [u8],
+///     ) -> Poll<io::Result<usize>> {
+///         let mut buf = tokio::io::ReadBuf::new(buf);
+///         ready!(Pin::new(&mut self.0).poll_read(cx, &mut buf))?;
+///         Poll::Ready(Ok(buf.filled().len()))
+///     }
+/// }
+///
+/// async fn from_tokio_file(path: &str) -> io::Result<Dex> {
+///     let file = tokio::fs::File::open(path).await?;
+///     Dex::from_async_reader(Tokio(file)).await
+/// }
+/// ```
+pub trait AsyncRead {
+	/// Attempts to read bytes into `buf`.
+	///
+	/// # Returns
+	/// - `Poll::Ready(Ok(n))` once `n` bytes were read, where `0` means the end of the input.
+	/// - `Poll::Ready(Err(e))` if reading failed.
+	/// - `Poll::Pending` if no bytes are available yet, after arranging for `cx` to be woken.
+	fn poll_read(
+		self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+		buf: &mut [u8],
+	) -> Poll<io::Result<usize>>;
+}
+
+/// Reads from the slice, which is always ready.
+impl AsyncRead for &[u8] {
+	fn poll_read(
+		mut self: Pin<&mut Self>,
+		_cx: &mut Context<'_>,
+		buf: &mut [u8],
+	) -> Poll<io::Result<usize>> {
+		let len = buf.len().min(self.len());
+		let (read, rest) = self.split_at(len);
+		buf[..len].copy_from_slice(read);
+		*self = rest;
+		Poll::Ready(Ok(len))
+	}
+}
+
+impl<R: AsyncRead + Unpin + ?Sized> AsyncRead for &mut R {
+	fn poll_read(
+		mut self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+		buf: &mut [u8],
+	) -> Poll<io::Result<usize>> {
+		Pin::new(&mut **self).poll_read(cx, buf)
+	}
+}
+
+/// Reads `reader` to its end, retrying reads that were interrupted like `Read::read_to_end`.
+pub(crate) async fn read_to_end<R: AsyncRead + Unpin>(mut reader: R) -> io::Result<Vec<u8>> {
+	let mut bytes = Vec::new();
+	let mut chunk = [0; 8192];
+	loop {
+		match poll_fn(|cx| Pin::new(&mut reader).poll_read(cx, &mut chunk)).await {
+			Ok(0) => return Ok(bytes),
+			Ok(read) => bytes.extend_from_slice(&chunk[..read]),
+			Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+			Err(e) => return Err(e),
+		}
+	}
+}
@@ -0,0 +1,47 @@
+//! Base64 decoding of pasted DEX data, available with the `base64` feature.
+
+/// Decodes standard base64 (RFC 4648), ignoring whitespace such as line wrapping.
+///
+/// The trailing `=` padding is optional.
+///
+/// # Returns
+/// - `Some(bytes)` if the text is valid base64.
+/// - `None` otherwise.
+pub fn decode(text: &str) -> Option<Vec<u8>> {
+	let digits: Vec<u8> = text.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+	let unpadded = match digits.iter().position(|&b| b == b'=') {
+		Some(start) if digits.len() % 4 == 0 && digits.len() - start <= 2 => {
+			if digits[start..].iter().any(|&b| b != b'=') {
+				return None;
+			}
+			&digits[..start]
+		}
+		Some(_) => return None,
+		None => &digits[..],
+	};
+	if unpadded.len() % 4 == 1 {
+		return None;
+	}
+	let mut out = Vec::with_capacity(unpadded.len() / 4 * 3 + 2);
+	for group in unpadded.chunks(4) {
+		let mut bits = 0u32;
+		for &digit in group {
+			bits = bits << 6 | value(digit)? as u32;
+		}
+		bits <<= 6 * (4 - group.len()) as u32;
+		out.extend_from_slice(&bits.to_be_bytes()[1..group.len()]);
+	}
+	Some(out)
+}
+
+/// Returns the 6-bit value of a base64 digit.
+fn value(digit: u8) -> Option<u8> {
+	match digit {
+		b'A'..=b'Z' => Some(digit - b'A'),
+		b'a'..=b'z' => Some(digit - b'a' + 26),
+		b'0'..=b'9' => Some(digit - b'0' + 52),
+		b'+' => Some(62),
+		b'/' => Some(63),
+		_ => None,
+	}
+}
@@ -0,0 +1,352 @@
+//! Correcting the checksums of many DEX files at once, reporting each file as it is done.
+
+use std::error::Error;
+use std::ffi::OsString;
+use std::fmt::Display;
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+use crate::Dex;
+use crate::DexError;
+
+/// How [`correct_files`] and [`correct_files_with`] process and write the files.
+#[derive(Debug, Clone)]
+pub struct BatchOptions {
+	/// The number of files to process in parallel, or `0` to use one thread per CPU.
+	///
+	/// Files are spread over scoped standard library threads; there is no thread pool
+	/// dependency such as `rayon`.
+	pub jobs: usize,
+	/// Also corrects the SHA-1 signature of every file, before the checksum that covers it.
+	pub signature: bool,
+	/// Computes what would be corrected without writing anything.
+	pub dry_run: bool,
+	/// Keeps the modification time of files corrected in place. Their permissions are always kept.
+	pub preserve_mtime: bool,
+	/// Writes every processed file under this directory instead of correcting it in place,
+	/// creating the directory if needed. Files keep their directories, so that files with the
+	/// same name in different directories do not overwrite each other.
+	pub out_dir: Option<PathBuf>,
+	/// The directory that paths are kept relative to under `out_dir`, usually the root of a
+	/// recursive search. Paths outside of it are kept whole.
+	pub base_dir: Option<PathBuf>,
+}
+
+impl Default for BatchOptions {
+	/// Processes one file at a time, correcting each in place.
+	fn default() -> Self {
+		BatchOptions {
+			jobs: 1,
+			signature: false,
+			dry_run: false,
+			preserve_mtime: false,
+			out_dir: None,
+			base_dir: None,
+		}
+	}
+}
+
+/// The outcome of correcting one file of a batch.
+#[derive(Debug, Default)]
+pub struct FileResult {
+	/// Whether the checksum, or the signature with [`BatchOptions::signature`], was wrong, and
+	/// corrected unless this is a dry run.
+	pub changed: bool,
+	/// The checksum in the header before the correction, if the header could be read.
+	pub old_checksum: Option<u32>,
+	/// The checksum the file should have, if the header could be read.
+	pub new_checksum: Option<u32>,
+	/// The signature in the header before the correction, if it was corrected too.
+	pub old_signature: Option<[u8; 20]>,
+	/// The signature the file should have, if it was corrected too.
+	pub new_signature: Option<[u8; 20]>,
+	/// Where the file was written, if it was.
+	pub output: Option<PathBuf>,
+	/// Why the file could not be corrected, if it could not.
+	pub error: Option<FileError>,
+}
+
+/// Why one file of a batch could not be corrected.
+#[derive(Debug)]
+pub enum FileError {
+	/// The file could not be read, or is too short to hold a DEX header.
+	Read(DexError),
+	/// The file is not a DEX file that can be corrected, for the given reason.
+	///
+	/// Files with a `.dex` extension are not necessarily DEX files, such as partial downloads
+	/// or backups, and correcting their "checksum" would damage them, so they are left
+	/// untouched. Callers usually skip them rather than treat them as failures.
+	NotADex(&'static str),
+	/// The checksum could not be corrected.
+	Correct(DexError),
+	/// The corrected file could not be written.
+	Write(io::Error),
+}
+
+impl Display for FileError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			FileError::Read(e) => write!(f, "failed to read: {}", e),
+			FileError::NotADex(reason) => write!(f, "failed to correct: {}", reason),
+			FileError::Correct(e) => write!(f, "failed to correct: {}", e),
+			FileError::Write(e) => write!(f, "failed to write: {}", e),
+		}
+	}
+}
+
+impl Error for FileError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			FileError::Read(e) | FileError::Correct(e) => Some(e),
+			FileError::Write(e) => Some(e),
+			FileError::NotADex(_) => None,
+		}
+	}
+}
+
+/// Corrects the checksums of many DEX files, like [`correct_files_with`] without a callback.
+pub fn correct_files<P: AsRef<Path> + Sync>(
+	paths: &[P],
+	options: &BatchOptions,
+) -> Vec<FileResult> {
+	correct_files_with(paths, options, |_, _| {})
+}
+
+/// Corrects the checksums of many DEX files, calling `on_file` as each file is done.
+///
+/// A file that fails does not stop the others from being processed. Files are processed on
+/// up to `options.jobs` threads, but `on_file` is always called on the calling thread, in the
+/// order the files are done, with the path of the file and its result. Nothing is printed, so
+/// `on_file` can update a progress bar or a GUI as well as print to a terminal.
+///
+/// # Returns
+/// One [`FileResult`] per path, in the order of `paths`.
+///
+/// # Examples
+///
+/// ```
+/// use dex_checksum_tools::batch::{correct_files_with, BatchOptions};
+///
+/// let paths = ["/path/to/classes.dex", "/path/to/classes2.dex"];
+/// let results = correct_files_with(&paths, &BatchOptions::default(), |path, result| {
+///     match &result.error {
+///         Some(e) => println!("{}: {}", path, e),
+///         None => println!("{}: changed {}", path, result.changed),
+///     }
+/// });
+/// assert_eq!(results.len(), 2);
+/// ```
+pub fn correct_files_with<P, F>(
+	paths: &[P],
+	options: &BatchOptions,
+	mut on_file: F,
+) -> Vec<FileResult>
+where
+	P: AsRef<Path> + Sync,
+	F: FnMut(&str, &FileResult),
+{
+	let jobs = match options.jobs {
+		0 => thread::available_parallelism().map_or(1, |n| n.get()),
+		jobs => jobs,
+	}
+	.min(paths.len());
+	let mut results: Vec<Option<FileResult>> = (0..paths.len()).map(|_| None).collect();
+	let mut done = |index: usize, result: FileResult| {
+		on_file(&paths[index].as_ref().to_string_lossy(), &result);
+		results[index] = Some(result);
+	};
+
+	if jobs <= 1 {
+		for (index, path) in paths.iter().enumerate() {
+			done(index, correct_file(path.as_ref(), options));
+		}
+	} else {
+		let next = AtomicUsize::new(0);
+		let (sender, receiver) = mpsc::channel();
+		thread::scope(|scope| {
+			for _ in 0..jobs {
+				let sender = sender.clone();
+				let next = &next;
+				scope.spawn(move || loop {
+					let index = next.fetch_add(1, Ordering::Relaxed);
+					if index >= paths.len() {
+						break;
+					}
+					let result = correct_file(paths[index].as_ref(), options);
+					if sender.send((index, result)).is_err() {
+						break;
+					}
+				});
+			}
+			drop(sender);
+			for (index, result) in receiver {
+				done(index, result);
+			}
+		});
+	}
+	results
+		.into_iter()
+		.map(|result| result.expect("Every file is processed once"))
+		.collect()
+}
+
+/// Corrects the checksum of one file of a batch.
+fn correct_file(path: &Path, options: &BatchOptions) -> FileResult {
+	let mut result = FileResult::default();
+	if let Err(e) = correct_file_into(path, options, &mut result) {
+		result.error = Some(e);
+	}
+	result
+}
+
+fn correct_file_into(
+	path: &Path,
+	options: &BatchOptions,
+	result: &mut FileResult,
+) -> Result<(), FileError> {
+	let mut dex = Dex::open(path).map_err(|e| FileError::Read(e.into()))?;
+	if dex.is_compact_dex() {
+		return Err(FileError::NotADex("CompactDex files are not supported"));
+	} else if dex.is_container_dex() {
+		return Err(FileError::NotADex(
+			"DEX containers (version 041 or later) are not supported",
+		));
+	} else if !dex.is_valid_magic() {
+		return Err(FileError::NotADex("not a DEX file"));
+	}
+	dex.validate_minimum_length().map_err(FileError::Read)?;
+	result.old_checksum = Some(dex.current_checksum_u32());
+	if options.signature {
+		result.old_signature = Some(dex.current_signature());
+		result.changed = dex.try_correct_all().map_err(FileError::Correct)?;
+		result.new_signature = Some(dex.current_signature());
+	} else {
+		result.changed = dex.try_correct_checksum().map_err(FileError::Correct)?;
+	}
+	// The expected checksum covers the signature, so it is only known once that is corrected.
+	result.new_checksum = Some(dex.current_checksum_u32());
+	if options.dry_run {
+		return Ok(());
+	}
+	if let Some(out_dir) = &options.out_dir {
+		let relative = match &options.base_dir {
+			Some(base_dir) => path.strip_prefix(base_dir).unwrap_or(path),
+			None => path,
+		};
+		let output = output_path(out_dir, relative);
+		if let Some(parent) = output.parent() {
+			fs::create_dir_all(parent).map_err(FileError::Write)?;
+		}
+		write_file(&dex, &output).map_err(FileError::Write)?;
+		result.output = Some(output);
+	} else if result.changed {
+		replace_file(&dex, path, options.preserve_mtime).map_err(FileError::Write)?;
+		result.output = Some(path.to_path_buf());
+	}
+	Ok(())
+}
+
+/// Maps an input path to where its output is written under `out_dir`.
+///
+/// Root, prefix, `.` and `..` components are dropped so that the output never ends up
+/// outside of `out_dir`.
+fn output_path(out_dir: &Path, path: &Path) -> PathBuf {
+	let relative: PathBuf = path
+		.components()
+		.filter(|component| matches!(component, Component::Normal(_)))
+		.collect();
+	out_dir.join(relative)
+}
+
+/// Writes a DEX file, compressing it again if the path ends in `.gz` and gzip support is enabled.
+pub fn write_file<P: AsRef<Path>>(dex: &Dex, path: P) -> io::Result<()> {
+	#[cfg(feature = "gzip")]
+	if path.as_ref().extension() == Some("gz".as_ref()) {
+		return dex.write_gzip_to(&mut File::create(path)?);
+	}
+	dex.write_to_file(path)
+}
+
+/// Replaces an existing DEX file through a temporary file and an atomic rename, so that it is
+/// never left half-written. The permissions of the original file are kept, and so is its
+/// modification time if `preserve_mtime` is set.
+///
+/// Like [`write_file`], the file is compressed again if its path ends in `.gz`.
+pub fn replace_file(dex: &Dex, path: &Path, preserve_mtime: bool) -> io::Result<()> {
+	let metadata = fs::metadata(path)?;
+	let mut temp_name = OsString::from(".");
+	temp_name.push(path.file_name().unwrap_or_default());
+	temp_name.push(format!(".{}.tmp", process::id()));
+	let temp_path = path.with_file_name(temp_name);
+	let result = write_file(dex, &temp_path).and_then(|_| {
+		let file = File::options().write(true).open(&temp_path)?;
+		if preserve_mtime {
+			file.set_modified(metadata.modified()?)?;
+		}
+		file.set_permissions(metadata.permissions())?;
+		file.sync_all()?;
+		fs::rename(&temp_path, path)
+	});
+	if result.is_err() {
+		let _ = fs::remove_file(&temp_path);
+	}
+	result
+}
+
+/// Copies a file to `<path><suffix>`, such as `classes.dex.bak`, before it is overwritten.
+///
+/// An existing backup is never overwritten. If `numbered` is set, the first free name of
+/// `<path><suffix>.1`, `<path><suffix>.2` and so on is used instead, otherwise this fails.
+/// The backup keeps the permissions of the file.
+///
+/// # Returns
+/// The path of the backup.
+///
+/// # Errors
+/// Returns an error of kind [`io::ErrorKind::AlreadyExists`] if the backup already exists and
+/// `numbered` is not set, or any error from reading the file or writing the backup.
+pub fn backup_file(path: &Path, suffix: &str, numbered: bool) -> io::Result<PathBuf> {
+	let mut input = File::open(path)?;
+	let mut name = path.as_os_str().to_owned();
+	name.push(suffix);
+	let mut number = 0;
+	loop {
+		let backup_path = match number {
+			0 => PathBuf::from(&name),
+			number => {
+				let mut numbered_name = name.clone();
+				numbered_name.push(format!(".{}", number));
+				PathBuf::from(numbered_name)
+			}
+		};
+		match File::options()
+			.write(true)
+			.create_new(true)
+			.open(&backup_path)
+		{
+			Ok(mut backup) => {
+				let result = io::copy(&mut input, &mut backup)
+					.and_then(|_| backup.set_permissions(input.metadata()?.permissions()))
+					.and_then(|_| backup.sync_all());
+				if result.is_err() {
+					let _ = fs::remove_file(&backup_path);
+				}
+				return result.map(|_| backup_path);
+			}
+			Err(e) if e.kind() == io::ErrorKind::AlreadyExists && numbered => number += 1,
+			Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+				return Err(io::Error::new(
+					e.kind(),
+					format!("backup {} already exists", backup_path.display()),
+				))
+			}
+			Err(e) => return Err(e),
+		}
+	}
+}
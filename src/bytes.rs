@@ -0,0 +1,76 @@
+//! The storage behind a `Dex`, either an owned buffer or a read-only memory map.
+
+use std::fmt::Debug;
+use std::ops::Deref;
+use std::ops::DerefMut;
+
+#[cfg(all(feature = "memmap", unix))]
+use crate::mmap::Mmap;
+
+pub(crate) enum Bytes {
+	Owned(Vec<u8>),
+	#[cfg(all(feature = "memmap", unix))]
+	Mapped(Mmap),
+}
+
+impl Bytes {
+	/// Returns the owned buffer, copying the mapped bytes into one first if needed.
+	pub(crate) fn to_mut(&mut self) -> &mut Vec<u8> {
+		match self {
+			Bytes::Owned(bytes) => bytes,
+			#[cfg(all(feature = "memmap", unix))]
+			Bytes::Mapped(map) => {
+				*self = Bytes::Owned(map.to_vec());
+				self.to_mut()
+			}
+		}
+	}
+
+	/// Consumes the storage and returns an owned buffer.
+	pub(crate) fn into_vec(self) -> Vec<u8> {
+		match self {
+			Bytes::Owned(bytes) => bytes,
+			#[cfg(all(feature = "memmap", unix))]
+			Bytes::Mapped(map) => map.to_vec(),
+		}
+	}
+}
+
+impl Deref for Bytes {
+	type Target = [u8];
+
+	fn deref(&self) -> &[u8] {
+		match self {
+			Bytes::Owned(bytes) => bytes,
+			#[cfg(all(feature = "memmap", unix))]
+			Bytes::Mapped(map) => map,
+		}
+	}
+}
+
+impl DerefMut for Bytes {
+	fn deref_mut(&mut self) -> &mut [u8] {
+		self.to_mut()
+	}
+}
+
+impl Debug for Bytes {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		Debug::fmt(&**self, f)
+	}
+}
+
+impl Clone for Bytes {
+	/// Clones into an owned buffer, since a mapping cannot be shared between two owners.
+	fn clone(&self) -> Self {
+		Bytes::Owned(self.to_vec())
+	}
+}
+
+impl PartialEq for Bytes {
+	fn eq(&self, other: &Self) -> bool {
+		**self == **other
+	}
+}
+
+impl Eq for Bytes {}
@@ -0,0 +1,127 @@
+use crate::adler::adler32;
+use crate::ensure_not_zip;
+use crate::header::HEADER_SIZE;
+use crate::DexError;
+use crate::Endianness;
+
+/// Computes the DEX checksum of any candidate buffer, without building a `Dex`.
+///
+/// This is the Adler-32 of `bytes[12..]`, laid out according to the buffer's `endian_tag`
+/// so it can be compared against, or written to, `bytes[8..12]` as-is. It lets tooling
+/// evaluate a hypothetical edit before committing it to a file.
+///
+/// # Arguments
+///
+/// * `bytes` - The complete contents of a candidate DEX file.
+///
+/// # Returns
+///
+/// A 4-byte array representing the checksum the buffer should carry.
+///
+/// # Panics
+/// Panics if `bytes` is shorter than 12 bytes.
+///
+/// # Examples
+///
+/// ```
+/// use dex_checksum_tools::checksum_of;
+///
+/// let mut candidate = std::fs::read("/path/to/input.dex").unwrap_or_else(|_| vec![0; 0x70]);
+/// candidate[0x70 - 1] ^= 0xff;
+/// println!("checksum after the edit: {:?}", checksum_of(&candidate));
+/// ```
+pub fn checksum_of(bytes: &[u8]) -> [u8; 4] {
+	Endianness::of(bytes).encode_u32(adler32(&bytes[12..]))
+}
+
+/// Checks whether the checksum stored in a candidate buffer matches its contents.
+///
+/// # Arguments
+///
+/// * `bytes` - The complete contents of a candidate DEX file.
+///
+/// # Returns
+///
+/// `true` if `bytes[8..12]` equals [`checksum_of`] the buffer, `false` otherwise.
+///
+/// # Panics
+/// Panics if `bytes` is shorter than 12 bytes.
+pub fn checksum_matches_for(bytes: &[u8]) -> bool {
+	bytes[8..12] == checksum_of(bytes)
+}
+
+/// Verifies a DEX file against an expected checksum obtained out of band, such as from a manifest.
+///
+/// The checksum stored in the header is ignored, which is useful when the header itself may
+/// have been tampered with.
+///
+/// # Arguments
+///
+/// * `bytes` - The complete contents of the DEX file.
+/// * `expected` - The trusted checksum, laid out like the header field, see [`checksum_of`].
+///
+/// # Returns
+///
+/// `Ok(true)` if the Adler-32 of `bytes[12..]` equals `expected`, `Ok(false)` otherwise.
+///
+/// # Errors
+/// - Returns [`DexError::TooShort`] if `bytes` is shorter than the 0x70-byte header.
+///
+/// # Examples
+///
+/// ```
+/// use dex_checksum_tools::verify_against;
+///
+/// if let Ok(bytes) = std::fs::read("/path/to/input.dex") {
+///     let trusted = [0x9d, 0x1c, 0x2b, 0x7a];
+///     println!("{:?}", verify_against(&bytes, trusted));
+/// }
+/// ```
+pub fn verify_against(bytes: &[u8], expected: [u8; 4]) -> Result<bool, DexError> {
+	ensure_header(bytes)?;
+	Ok(checksum_of(bytes) == expected)
+}
+
+/// Verifies the checksum stored in a DEX file held in a borrowed buffer, without copying it.
+///
+/// This is [`crate::Dex::check_checksum`] for bytes that already live elsewhere, such as a
+/// DEX entry stored uncompressed inside an APK that was read into memory as a whole.
+///
+/// # Arguments
+///
+/// * `buf` - The complete contents of the DEX file.
+///
+/// # Returns
+///
+/// `Ok(true)` if `buf[8..12]` equals the Adler-32 of `buf[12..]`, `Ok(false)` otherwise.
+///
+/// # Errors
+/// - Returns [`DexError::NotADex`] if `buf` holds a ZIP archive, such as an APK.
+/// - Returns [`DexError::TooShort`] if `buf` is shorter than the 0x70-byte header.
+///
+/// # Examples
+///
+/// ```
+/// use dex_checksum_tools::check_checksum_slice;
+/// use dex_checksum_tools::Dex;
+///
+/// let bytes = Dex::empty(35).into_bytes();
+/// assert!(check_checksum_slice(&bytes).unwrap());
+/// assert!(check_checksum_slice(&bytes[..0x10]).is_err());
+/// ```
+pub fn check_checksum_slice(buf: &[u8]) -> Result<bool, DexError> {
+	ensure_not_zip(buf)?;
+	ensure_header(buf)?;
+	Ok(checksum_matches_for(buf))
+}
+
+fn ensure_header(bytes: &[u8]) -> Result<(), DexError> {
+	if bytes.len() < HEADER_SIZE {
+		Err(DexError::TooShort {
+			needed: HEADER_SIZE,
+			got: bytes.len(),
+		})
+	} else {
+		Ok(())
+	}
+}
@@ -0,0 +1,32 @@
+//! The CRC-32 (IEEE 802.3) checksum used by the ZIP format.
+
+const TABLE: [u32; 256] = make_table();
+
+const fn make_table() -> [u32; 256] {
+	let mut table = [0u32; 256];
+	let mut i = 0;
+	while i < 256 {
+		let mut crc = i as u32;
+		let mut bit = 0;
+		while bit < 8 {
+			crc = if crc & 1 != 0 {
+				0xEDB88320 ^ (crc >> 1)
+			} else {
+				crc >> 1
+			};
+			bit += 1;
+		}
+		table[i] = crc;
+		i += 1;
+	}
+	table
+}
+
+/// Computes the CRC-32 of `data` in one call.
+pub fn crc32(data: &[u8]) -> u32 {
+	let mut crc = !0u32;
+	for &byte in data {
+		crc = TABLE[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8);
+	}
+	!crc
+}
@@ -0,0 +1,473 @@
+//! A small, dependency-free implementation of the DEFLATE format (RFC 1951).
+
+/// The base lengths for length codes 257 through 285.
+const LENGTH_BASE: [u16; 29] = [
+	3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+	163, 195, 227, 258,
+];
+
+/// The number of extra bits for length codes 257 through 285.
+const LENGTH_EXTRA: [u8; 29] = [
+	0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+
+/// The base distances for distance codes 0 through 29.
+const DIST_BASE: [u16; 30] = [
+	1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049,
+	3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+
+/// The number of extra bits for distance codes 0 through 29.
+const DIST_EXTRA: [u8; 30] = [
+	0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13,
+];
+
+/// The order in which code length code lengths are stored in a dynamic block header.
+const CODE_LENGTH_ORDER: [usize; 19] = [
+	16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+/// The error of [`inflate_limited`] once the output grows beyond its limit.
+const TOO_LONG: &str = "decompressed data is longer than expected";
+
+/// Decompresses a raw DEFLATE stream.
+///
+/// # Errors
+/// Returns a short description of the problem if the stream is malformed or truncated.
+pub fn inflate(input: &[u8]) -> Result<Vec<u8>, &'static str> {
+	inflate_limited(input, usize::MAX)
+}
+
+/// Decompresses a raw DEFLATE stream like [`inflate`], giving up as soon as the output would
+/// grow beyond `limit` bytes, so that a small crafted stream cannot exhaust memory.
+///
+/// # Errors
+/// Returns a short description of the problem if the stream is malformed or truncated, or
+/// decompresses to more than `limit` bytes.
+pub fn inflate_limited(input: &[u8], limit: usize) -> Result<Vec<u8>, &'static str> {
+	let mut reader = BitReader::new(input);
+	let mut out = Vec::with_capacity(input.len().saturating_mul(3).min(limit));
+	loop {
+		let last = reader.bits(1)? == 1;
+		match reader.bits(2)? {
+			0 => {
+				reader.align();
+				let len = reader.bits(16)? as u16;
+				let nlen = reader.bits(16)? as u16;
+				if len != !nlen {
+					return Err("stored block length mismatch");
+				}
+				if out.len() + len as usize > limit {
+					return Err(TOO_LONG);
+				}
+				out.extend_from_slice(reader.take(len as usize)?);
+			}
+			1 => {
+				let (lit, dist) = fixed_tables();
+				inflate_block(&mut reader, &mut out, &lit, &dist, limit)?;
+			}
+			2 => {
+				let (lit, dist) = dynamic_tables(&mut reader)?;
+				inflate_block(&mut reader, &mut out, &lit, &dist, limit)?;
+			}
+			_ => return Err("invalid block type"),
+		}
+		if last {
+			return Ok(out);
+		}
+	}
+}
+
+fn inflate_block(
+	reader: &mut BitReader,
+	out: &mut Vec<u8>,
+	lit: &Huffman,
+	dist: &Huffman,
+	limit: usize,
+) -> Result<(), &'static str> {
+	loop {
+		let symbol = lit.decode(reader)? as usize;
+		match symbol {
+			0..=255 if out.len() >= limit => return Err(TOO_LONG),
+			0..=255 => out.push(symbol as u8),
+			256 => return Ok(()),
+			257..=285 => {
+				let index = symbol - 257;
+				let length =
+					LENGTH_BASE[index] as usize + reader.bits(LENGTH_EXTRA[index] as u32)? as usize;
+				let index = dist.decode(reader)? as usize;
+				if index >= 30 {
+					return Err("invalid distance code");
+				}
+				let distance = DIST_BASE[index] as usize + reader.bits(DIST_EXTRA[index] as u32)? as usize;
+				if distance > out.len() {
+					return Err("distance too far back");
+				}
+				if out.len() + length > limit {
+					return Err(TOO_LONG);
+				}
+				let start = out.len() - distance;
+				for i in 0..length {
+					out.push(out[start + i]);
+				}
+			}
+			_ => return Err("invalid literal/length code"),
+		}
+	}
+}
+
+fn fixed_tables() -> (Huffman, Huffman) {
+	let mut lengths = [0u8; 288];
+	lengths[0..144].fill(8);
+	lengths[144..256].fill(9);
+	lengths[256..280].fill(7);
+	lengths[280..288].fill(8);
+	(Huffman::new(&lengths), Huffman::new(&[5; 30]))
+}
+
+fn dynamic_tables(reader: &mut BitReader) -> Result<(Huffman, Huffman), &'static str> {
+	let hlit = reader.bits(5)? as usize + 257;
+	let hdist = reader.bits(5)? as usize + 1;
+	let hclen = reader.bits(4)? as usize + 4;
+
+	let mut code_lengths = [0u8; 19];
+	for &index in CODE_LENGTH_ORDER.iter().take(hclen) {
+		code_lengths[index] = reader.bits(3)? as u8;
+	}
+	let code_length_huffman = Huffman::new(&code_lengths);
+
+	let mut lengths = vec![0u8; hlit + hdist];
+	let mut i = 0;
+	while i < lengths.len() {
+		let symbol = code_length_huffman.decode(reader)?;
+		let (value, repeat) = match symbol {
+			0..=15 => (symbol as u8, 1),
+			16 => {
+				if i == 0 {
+					return Err("repeat with no previous length");
+				}
+				(lengths[i - 1], 3 + reader.bits(2)? as usize)
+			}
+			17 => (0, 3 + reader.bits(3)? as usize),
+			18 => (0, 11 + reader.bits(7)? as usize),
+			_ => return Err("invalid code length code"),
+		};
+		if i + repeat > lengths.len() {
+			return Err("too many code lengths");
+		}
+		lengths[i..i + repeat].fill(value);
+		i += repeat;
+	}
+	if lengths[256] == 0 {
+		return Err("missing end-of-block code");
+	}
+	Ok((
+		Huffman::new(&lengths[..hlit]),
+		Huffman::new(&lengths[hlit..]),
+	))
+}
+
+/// A canonical Huffman decoding table.
+struct Huffman {
+	counts: [u16; 16],
+	symbols: Vec<u16>,
+}
+
+impl Huffman {
+	fn new(lengths: &[u8]) -> Self {
+		let mut counts = [0u16; 16];
+		for &length in lengths {
+			counts[length as usize] += 1;
+		}
+		counts[0] = 0;
+
+		let mut offsets = [0u16; 16];
+		for i in 1..16 {
+			offsets[i] = offsets[i - 1] + counts[i - 1];
+		}
+		let mut symbols = vec![0u16; lengths.len()];
+		for (symbol, &length) in lengths.iter().enumerate() {
+			if length != 0 {
+				symbols[offsets[length as usize] as usize] = symbol as u16;
+				offsets[length as usize] += 1;
+			}
+		}
+		Huffman { counts, symbols }
+	}
+
+	fn decode(&self, reader: &mut BitReader) -> Result<u16, &'static str> {
+		let mut code: i32 = 0;
+		let mut first: i32 = 0;
+		let mut index: i32 = 0;
+		for length in 1..16 {
+			code |= reader.bits(1)? as i32;
+			let count = self.counts[length] as i32;
+			if code - first < count {
+				return Ok(self.symbols[(index + code - first) as usize]);
+			}
+			index += count;
+			first += count;
+			first <<= 1;
+			code <<= 1;
+		}
+		Err("invalid Huffman code")
+	}
+}
+
+/// Reads bits least-significant first, as DEFLATE requires.
+struct BitReader<'a> {
+	input: &'a [u8],
+	position: usize,
+	bit_buffer: u32,
+	bit_count: u32,
+}
+
+impl<'a> BitReader<'a> {
+	fn new(input: &'a [u8]) -> Self {
+		BitReader {
+			input,
+			position: 0,
+			bit_buffer: 0,
+			bit_count: 0,
+		}
+	}
+
+	fn bits(&mut self, count: u32) -> Result<u32, &'static str> {
+		while self.bit_count < count {
+			let byte = *self
+				.input
+				.get(self.position)
+				.ok_or("unexpected end of stream")?;
+			self.position += 1;
+			self.bit_buffer |= (byte as u32) << self.bit_count;
+			self.bit_count += 8;
+		}
+		let value = self.bit_buffer & ((1u64 << count) - 1) as u32;
+		self.bit_buffer >>= count;
+		self.bit_count -= count;
+		Ok(value)
+	}
+
+	fn align(&mut self) {
+		self.bit_buffer = 0;
+		self.bit_count = 0;
+	}
+
+	fn take(&mut self, len: usize) -> Result<&'a [u8], &'static str> {
+		let end = self
+			.position
+			.checked_add(len)
+			.filter(|&end| end <= self.input.len())
+			.ok_or("unexpected end of stream")?;
+		let slice = &self.input[self.position..end];
+		self.position = end;
+		Ok(slice)
+	}
+}
+
+/// The size of the sliding window searched for matches.
+const WINDOW_SIZE: usize = 32 * 1024;
+
+/// The number of bits in the hash of the next three bytes.
+const HASH_BITS: u32 = 15;
+
+/// How many earlier positions with the same hash are tried before settling on a match.
+const MAX_CHAIN: usize = 64;
+
+/// The shortest and longest matches DEFLATE can encode.
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+
+/// Compresses data into a raw DEFLATE stream.
+///
+/// The data is encoded as a single block with the fixed Huffman codes, using a hash-chain
+/// search for matches. If that does not end up smaller than the input, the data is stored
+/// uncompressed instead.
+pub fn deflate(input: &[u8]) -> Vec<u8> {
+	let compressed = deflate_fixed(input);
+	if compressed.len() < input.len() + 5 * (input.len() / 0xffff + 1) {
+		compressed
+	} else {
+		deflate_stored(input)
+	}
+}
+
+fn deflate_stored(input: &[u8]) -> Vec<u8> {
+	let mut out = Vec::with_capacity(input.len() + 5 * (input.len() / 0xffff + 1));
+	let mut chunks = input.chunks(0xffff).peekable();
+	if chunks.peek().is_none() {
+		out.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+	}
+	while let Some(chunk) = chunks.next() {
+		let len = chunk.len() as u16;
+		out.push(chunks.peek().is_none() as u8);
+		out.extend_from_slice(&len.to_le_bytes());
+		out.extend_from_slice(&(!len).to_le_bytes());
+		out.extend_from_slice(chunk);
+	}
+	out
+}
+
+fn deflate_fixed(input: &[u8]) -> Vec<u8> {
+	let mut writer = BitWriter::new(input.len() / 2);
+	writer.bits(1, 1);
+	writer.bits(1, 2);
+	let mut matcher = Matcher::new(input);
+	let mut position = 0;
+	while position < input.len() {
+		let (length, distance) = matcher.longest_match(position);
+		if length >= MIN_MATCH {
+			write_length(&mut writer, length);
+			write_distance(&mut writer, distance);
+			for p in position..position + length {
+				matcher.insert(p);
+			}
+			position += length;
+		} else {
+			write_literal(&mut writer, input[position] as u16);
+			matcher.insert(position);
+			position += 1;
+		}
+	}
+	write_literal(&mut writer, 256);
+	writer.finish()
+}
+
+/// Finds earlier occurrences of the bytes at a position through chains of equal hashes.
+struct Matcher<'a> {
+	input: &'a [u8],
+	/// The most recent position for each hash.
+	head: Vec<usize>,
+	/// The previous position with the same hash, for each position in the window.
+	prev: Vec<usize>,
+}
+
+impl<'a> Matcher<'a> {
+	fn new(input: &'a [u8]) -> Self {
+		Matcher {
+			input,
+			head: vec![usize::MAX; 1 << HASH_BITS],
+			prev: vec![usize::MAX; WINDOW_SIZE],
+		}
+	}
+
+	fn hash(&self, position: usize) -> usize {
+		let bytes = &self.input[position..position + MIN_MATCH];
+		let key = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], 0]);
+		(key.wrapping_mul(2654435761) >> (32 - HASH_BITS)) as usize
+	}
+
+	fn insert(&mut self, position: usize) {
+		if position + MIN_MATCH <= self.input.len() {
+			let hash = self.hash(position);
+			self.prev[position % WINDOW_SIZE] = self.head[hash];
+			self.head[hash] = position;
+		}
+	}
+
+	/// Returns the length and distance of the longest match, or a length of 0 if there is none.
+	fn longest_match(&self, position: usize) -> (usize, usize) {
+		if position + MIN_MATCH > self.input.len() {
+			return (0, 0);
+		}
+		let max = (self.input.len() - position).min(MAX_MATCH);
+		let mut best = (0, 0);
+		let mut candidate = self.head[self.hash(position)];
+		for _ in 0..MAX_CHAIN {
+			if candidate == usize::MAX || position - candidate > WINDOW_SIZE {
+				break;
+			}
+			let length = self.input[candidate..]
+				.iter()
+				.zip(&self.input[position..position + max])
+				.take_while(|(a, b)| a == b)
+				.count();
+			if length > best.0 {
+				best = (length, position - candidate);
+				if length == max {
+					break;
+				}
+			}
+			let next = self.prev[candidate % WINDOW_SIZE];
+			// Older entries in the chain may have been overwritten by newer positions.
+			if next == usize::MAX || next >= candidate {
+				break;
+			}
+			candidate = next;
+		}
+		best
+	}
+}
+
+/// Writes a literal/length symbol with the fixed Huffman code.
+fn write_literal(writer: &mut BitWriter, symbol: u16) {
+	match symbol {
+		0..=143 => writer.code(0x30 + symbol as u32, 8),
+		144..=255 => writer.code(0x190 + (symbol - 144) as u32, 9),
+		256..=279 => writer.code((symbol - 256) as u32, 7),
+		_ => writer.code(0xc0 + (symbol - 280) as u32, 8),
+	}
+}
+
+fn write_length(writer: &mut BitWriter, length: usize) {
+	let index = LENGTH_BASE
+		.iter()
+		.rposition(|&base| base as usize <= length)
+		.unwrap();
+	write_literal(writer, 257 + index as u16);
+	writer.bits(
+		(length - LENGTH_BASE[index] as usize) as u32,
+		LENGTH_EXTRA[index] as u32,
+	);
+}
+
+fn write_distance(writer: &mut BitWriter, distance: usize) {
+	let index = DIST_BASE
+		.iter()
+		.rposition(|&base| base as usize <= distance)
+		.unwrap();
+	writer.code(index as u32, 5);
+	writer.bits(
+		(distance - DIST_BASE[index] as usize) as u32,
+		DIST_EXTRA[index] as u32,
+	);
+}
+
+/// Writes bits least-significant first, as DEFLATE requires.
+struct BitWriter {
+	out: Vec<u8>,
+	bit_buffer: u64,
+	bit_count: u32,
+}
+
+impl BitWriter {
+	fn new(capacity: usize) -> Self {
+		BitWriter {
+			out: Vec::with_capacity(capacity),
+			bit_buffer: 0,
+			bit_count: 0,
+		}
+	}
+
+	fn bits(&mut self, value: u32, count: u32) {
+		self.bit_buffer |= (value as u64) << self.bit_count;
+		self.bit_count += count;
+		while self.bit_count >= 8 {
+			self.out.push(self.bit_buffer as u8);
+			self.bit_buffer >>= 8;
+			self.bit_count -= 8;
+		}
+	}
+
+	/// Writes a Huffman code, which DEFLATE stores most-significant bit first.
+	fn code(&mut self, code: u32, length: u32) {
+		self.bits(code.reverse_bits() >> (32 - length), length);
+	}
+
+	fn finish(mut self) -> Vec<u8> {
+		if self.bit_count > 0 {
+			self.out.push(self.bit_buffer as u8);
+		}
+		self.out
+	}
+}
@@ -0,0 +1,71 @@
+use crate::adler;
+use crate::adler::Adler32;
+use crate::sha1::Sha1;
+use crate::Endianness;
+
+/// The size of the chunks fed to both hashers in turn, small enough to stay in cache.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// A `Digests` structure that holds the expected checksum and signature of a DEX file,
+/// computed in a single pass over its bytes.
+///
+/// The checksum covers the signature, so there are two expected checksums: one for the file
+/// as it is, and one for the file once its signature has been corrected.
+///
+/// # Examples
+///
+/// ```
+/// use dex_checksum_tools::Dex;
+///
+/// # #[cfg(feature = "std-fs")]
+/// if let Ok(dex) = Dex::open("/path/to/input.dex") {
+///     let digests = dex.compute_digests();
+///     println!("checksum valid: {}", digests.checksum() == dex.current_checksum());
+///     println!("signature valid: {}", digests.signature() == dex.current_signature());
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Digests {
+	checksum: [u8; 4],
+	corrected_checksum: [u8; 4],
+	signature: [u8; 20],
+}
+
+impl Digests {
+	/// Hashes `bytes[32..]` once, feeding each chunk into both the Adler-32 and the SHA-1.
+	pub(crate) fn compute(bytes: &[u8], endianness: Endianness) -> Digests {
+		let mut adler = Adler32::new();
+		let mut sha1 = Sha1::new();
+		for chunk in bytes[32..].chunks(CHUNK_SIZE) {
+			adler.update(chunk);
+			sha1.update(chunk);
+		}
+		let data = adler.hash();
+		let data_len = bytes.len() - 32;
+		let signature = sha1.finalize();
+		let checksum_with = |signature: &[u8]| {
+			let prefix = adler::adler32(signature);
+			endianness.encode_u32(adler::combine(prefix, data, data_len))
+		};
+		Digests {
+			checksum: checksum_with(&bytes[12..32]),
+			corrected_checksum: checksum_with(&signature),
+			signature,
+		}
+	}
+
+	/// The expected checksum of the file as it is, like [`crate::Dex::expect_checksum`].
+	pub fn checksum(&self) -> [u8; 4] {
+		self.checksum
+	}
+
+	/// The expected checksum of the file once its signature has been corrected.
+	pub fn corrected_checksum(&self) -> [u8; 4] {
+		self.corrected_checksum
+	}
+
+	/// The expected signature, like [`crate::Dex::expect_signature`].
+	pub fn signature(&self) -> [u8; 20] {
+		self.signature
+	}
+}
@@ -0,0 +1,107 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::io;
+
+/// An error that can occur while reading or validating a DEX file.
+#[derive(Debug)]
+pub enum DexError {
+	/// An I/O error occurred while reading or writing the DEX file.
+	Io(io::Error),
+
+	/// The DEX file is too short to contain the requested data.
+	///
+	/// * `needed` - The minimum number of bytes required.
+	/// * `got` - The actual number of bytes in the DEX file.
+	TooShort { needed: usize, got: usize },
+
+	/// The DEX file does not start with a valid `dex\n???\0` magic.
+	BadMagic([u8; 8]),
+
+	/// The file uses a format this crate cannot correct, such as an ART CompactDex (`cdex`) file
+	/// or a DEX container of version 041 or later.
+	UnsupportedFormat([u8; 8]),
+
+	/// A byte range lies outside of the DEX file, or outside of the area it is allowed to touch.
+	///
+	/// * `start` - The start of the requested range.
+	/// * `end` - The end of the requested range.
+	/// * `len` - The actual number of bytes in the DEX file.
+	OffsetOutOfRange {
+		start: usize,
+		end: usize,
+		len: usize,
+	},
+
+	/// The bytes expected by an edit do not match the bytes in the DEX file.
+	EditMismatch { offset: usize },
+
+	/// The string data at `offset` is not a well-formed MUTF-8 string.
+	InvalidString { offset: usize },
+
+	/// The file is not a DEX file at all, but something commonly mistaken for one.
+	///
+	/// * `looks_like` - What the file looks like instead, such as `"zip"` for an APK.
+	NotADex { looks_like: &'static str },
+
+	/// Text passed to a constructor such as `Dex::from_base64` is not validly encoded.
+	Decode(String),
+
+	/// The ZIP archive (such as an APK) is malformed or uses an unsupported feature.
+	Zip(String),
+}
+
+impl Display for DexError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			DexError::Io(e) => write!(f, "I/O error: {}", e),
+			DexError::TooShort { needed, got } => write!(
+				f,
+				"DEX file is too short: needed at least {} bytes, got {}",
+				needed, got
+			),
+			DexError::BadMagic(magic) => write!(f, "Bad DEX magic: {:?}", magic),
+			DexError::UnsupportedFormat(magic) => {
+				write!(f, "Unsupported format: {}", magic.escape_ascii())
+			}
+			DexError::OffsetOutOfRange { start, end, len } => write!(
+				f,
+				"Range {}..{} is out of range for a DEX file of {} bytes",
+				start, end, len
+			),
+			DexError::EditMismatch { offset } => {
+				write!(
+					f,
+					"Edit at offset {} does not match the current bytes",
+					offset
+				)
+			}
+			DexError::InvalidString { offset } => {
+				write!(f, "Malformed string data at offset {}", offset)
+			}
+			DexError::NotADex { looks_like: "zip" } => write!(
+				f,
+				"Not a DEX file but a ZIP archive, such as an APK: use correct-apk or verify-apk instead"
+			),
+			DexError::NotADex { looks_like } => {
+				write!(f, "Not a DEX file, it looks like a {} file", looks_like)
+			}
+			DexError::Decode(message) => write!(f, "Unable to decode DEX data: {}", message),
+			DexError::Zip(message) => write!(f, "Bad ZIP archive: {}", message),
+		}
+	}
+}
+
+impl Error for DexError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			DexError::Io(e) => Some(e),
+			_ => None,
+		}
+	}
+}
+
+impl From<io::Error> for DexError {
+	fn from(e: io::Error) -> Self {
+		DexError::Io(e)
+	}
+}
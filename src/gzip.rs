@@ -0,0 +1,74 @@
+//! Transparent gzip support for DEX files, available with the `gzip` feature.
+
+use std::io;
+
+use crate::crc32::crc32;
+use crate::deflate;
+
+const MAGIC: [u8; 2] = [0x1f, 0x8b];
+const METHOD_DEFLATED: u8 = 8;
+
+const FLAG_HCRC: u8 = 0x02;
+const FLAG_EXTRA: u8 = 0x04;
+const FLAG_NAME: u8 = 0x08;
+const FLAG_COMMENT: u8 = 0x10;
+
+/// Checks if `bytes` starts with the gzip magic `1f 8b`.
+pub fn is_gzip(bytes: &[u8]) -> bool {
+	bytes.starts_with(&MAGIC)
+}
+
+/// Decompresses a single-member gzip file, verifying its CRC-32 and length.
+pub fn decompress(bytes: &[u8]) -> io::Result<Vec<u8>> {
+	let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+	if bytes.len() < 18 || !is_gzip(bytes) {
+		return Err(invalid("gzip file is truncated"));
+	}
+	if bytes[2] != METHOD_DEFLATED {
+		return Err(invalid("gzip file uses an unsupported compression method"));
+	}
+	let flags = bytes[3];
+	let mut position = 10;
+	if flags & FLAG_EXTRA != 0 {
+		let len = bytes
+			.get(position..position + 2)
+			.ok_or_else(|| invalid("gzip file is truncated"))?;
+		position += 2 + u16::from_le_bytes([len[0], len[1]]) as usize;
+	}
+	for flag in [FLAG_NAME, FLAG_COMMENT] {
+		if flags & flag != 0 {
+			let len = bytes
+				.get(position..)
+				.and_then(|rest| rest.iter().position(|&b| b == 0))
+				.ok_or_else(|| invalid("gzip file is truncated"))?;
+			position += len + 1;
+		}
+	}
+	if flags & FLAG_HCRC != 0 {
+		position += 2;
+	}
+	let trailer = bytes.len() - 8;
+	let compressed = bytes
+		.get(position..trailer)
+		.ok_or_else(|| invalid("gzip file is truncated"))?;
+	let data = deflate::inflate(compressed).map_err(invalid)?;
+	let expected_crc = u32::from_le_bytes(bytes[trailer..trailer + 4].try_into().unwrap());
+	let expected_len = u32::from_le_bytes(bytes[trailer + 4..].try_into().unwrap());
+	if crc32(&data) != expected_crc || data.len() as u32 != expected_len {
+		return Err(invalid("gzip file is corrupt"));
+	}
+	Ok(data)
+}
+
+/// Compresses `bytes` into a gzip file with no name or timestamp.
+pub fn compress(bytes: &[u8]) -> Vec<u8> {
+	let compressed = deflate::deflate(bytes);
+	let mut out = Vec::with_capacity(compressed.len() + 18);
+	out.extend_from_slice(&MAGIC);
+	// Method, flags, mtime, extra flags and OS (unknown).
+	out.extend_from_slice(&[METHOD_DEFLATED, 0, 0, 0, 0, 0, 0, 0xff]);
+	out.extend_from_slice(&compressed);
+	out.extend_from_slice(&crc32(bytes).to_le_bytes());
+	out.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+	out
+}
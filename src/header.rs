@@ -0,0 +1,243 @@
+use crate::DexError;
+use crate::Endianness;
+
+/// The size in bytes of a standard DEX header.
+pub(crate) const HEADER_SIZE: usize = 0x70;
+
+/// A `DexHeader` structure that holds the decoded fields of a DEX file header.
+///
+/// All integer fields are decoded according to the file's `endian_tag`.
+///
+/// # Examples
+///
+/// ```
+/// use dex_checksum_tools::Dex;
+///
+/// # #[cfg(feature = "std-fs")]
+/// if let Ok(dex) = Dex::open("/path/to/input.dex") {
+///     if let Ok(header) = dex.header() {
+///         println!("file_size {}", header.file_size());
+///         println!("header_size {:#x}", header.header_size());
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DexHeader {
+	magic: [u8; 8],
+	checksum: u32,
+	signature: [u8; 20],
+	file_size: u32,
+	header_size: u32,
+	endian_tag: u32,
+	link_size: u32,
+	link_off: u32,
+	map_off: u32,
+	string_ids_size: u32,
+	string_ids_off: u32,
+	type_ids_size: u32,
+	type_ids_off: u32,
+	proto_ids_size: u32,
+	proto_ids_off: u32,
+	field_ids_size: u32,
+	field_ids_off: u32,
+	method_ids_size: u32,
+	method_ids_off: u32,
+	class_defs_size: u32,
+	class_defs_off: u32,
+	data_size: u32,
+	data_off: u32,
+}
+
+impl DexHeader {
+	/// Decodes a header from the first 0x70 bytes of a DEX file.
+	pub(crate) fn parse(bytes: &[u8], endianness: Endianness) -> Result<DexHeader, DexError> {
+		if bytes.len() < HEADER_SIZE {
+			return Err(DexError::TooShort {
+				needed: HEADER_SIZE,
+				got: bytes.len(),
+			});
+		}
+		let u32_at =
+			|offset: usize| endianness.decode_u32(bytes[offset..offset + 4].try_into().unwrap());
+		Ok(DexHeader {
+			magic: bytes[0..8].try_into().unwrap(),
+			checksum: u32_at(8),
+			signature: bytes[12..32].try_into().unwrap(),
+			file_size: u32_at(32),
+			header_size: u32_at(36),
+			endian_tag: u32_at(40),
+			link_size: u32_at(44),
+			link_off: u32_at(48),
+			map_off: u32_at(52),
+			string_ids_size: u32_at(56),
+			string_ids_off: u32_at(60),
+			type_ids_size: u32_at(64),
+			type_ids_off: u32_at(68),
+			proto_ids_size: u32_at(72),
+			proto_ids_off: u32_at(76),
+			field_ids_size: u32_at(80),
+			field_ids_off: u32_at(84),
+			method_ids_size: u32_at(88),
+			method_ids_off: u32_at(92),
+			class_defs_size: u32_at(96),
+			class_defs_off: u32_at(100),
+			data_size: u32_at(104),
+			data_off: u32_at(108),
+		})
+	}
+
+	/// Checks every section declared in the header against the bytes of the file.
+	pub(crate) fn validate_sections(&self, bytes: &[u8]) -> Result<(), DexError> {
+		// The number of items and offset of each section, with the size of one item in bytes.
+		let sections = [
+			(self.link_size, self.link_off, 1),
+			(self.string_ids_size, self.string_ids_off, 4),
+			(self.type_ids_size, self.type_ids_off, 4),
+			(self.proto_ids_size, self.proto_ids_off, 12),
+			(self.field_ids_size, self.field_ids_off, 8),
+			(self.method_ids_size, self.method_ids_off, 8),
+			(self.class_defs_size, self.class_defs_off, 32),
+			(self.data_size, self.data_off, 1),
+		];
+		for (size, off, item_size) in sections {
+			check_table(bytes, size, off, item_size)?;
+		}
+		Ok(())
+	}
+
+	/// The magic at offset 0, e.g. `dex\n035\0`.
+	pub fn magic(&self) -> [u8; 8] {
+		self.magic
+	}
+
+	/// The Adler-32 checksum stored at offset 8.
+	pub fn checksum(&self) -> u32 {
+		self.checksum
+	}
+
+	/// The SHA-1 signature stored at offset 12.
+	pub fn signature(&self) -> [u8; 20] {
+		self.signature
+	}
+
+	/// The size of the entire file in bytes.
+	pub fn file_size(&self) -> u32 {
+		self.file_size
+	}
+
+	/// The size of the header in bytes, normally `0x70`.
+	pub fn header_size(&self) -> u32 {
+		self.header_size
+	}
+
+	/// The endianness tag, `0x12345678` once decoded.
+	pub fn endian_tag(&self) -> u32 {
+		self.endian_tag
+	}
+
+	/// The size of the link section, or `0` if the file is not statically linked.
+	pub fn link_size(&self) -> u32 {
+		self.link_size
+	}
+
+	/// The offset of the link section.
+	pub fn link_off(&self) -> u32 {
+		self.link_off
+	}
+
+	/// The offset of the map list.
+	pub fn map_off(&self) -> u32 {
+		self.map_off
+	}
+
+	/// The number of strings in the string identifiers list.
+	pub fn string_ids_size(&self) -> u32 {
+		self.string_ids_size
+	}
+
+	/// The offset of the string identifiers list.
+	pub fn string_ids_off(&self) -> u32 {
+		self.string_ids_off
+	}
+
+	/// The number of elements in the type identifiers list.
+	pub fn type_ids_size(&self) -> u32 {
+		self.type_ids_size
+	}
+
+	/// The offset of the type identifiers list.
+	pub fn type_ids_off(&self) -> u32 {
+		self.type_ids_off
+	}
+
+	/// The number of elements in the prototype identifiers list.
+	pub fn proto_ids_size(&self) -> u32 {
+		self.proto_ids_size
+	}
+
+	/// The offset of the prototype identifiers list.
+	pub fn proto_ids_off(&self) -> u32 {
+		self.proto_ids_off
+	}
+
+	/// The number of elements in the field identifiers list.
+	pub fn field_ids_size(&self) -> u32 {
+		self.field_ids_size
+	}
+
+	/// The offset of the field identifiers list.
+	pub fn field_ids_off(&self) -> u32 {
+		self.field_ids_off
+	}
+
+	/// The number of elements in the method identifiers list.
+	pub fn method_ids_size(&self) -> u32 {
+		self.method_ids_size
+	}
+
+	/// The offset of the method identifiers list.
+	pub fn method_ids_off(&self) -> u32 {
+		self.method_ids_off
+	}
+
+	/// The number of elements in the class definitions list.
+	pub fn class_defs_size(&self) -> u32 {
+		self.class_defs_size
+	}
+
+	/// The offset of the class definitions list.
+	pub fn class_defs_off(&self) -> u32 {
+		self.class_defs_off
+	}
+
+	/// The size of the data section in bytes.
+	pub fn data_size(&self) -> u32 {
+		self.data_size
+	}
+
+	/// The offset of the data section.
+	pub fn data_off(&self) -> u32 {
+		self.data_off
+	}
+}
+
+/// Checks that a table of `size` items of `item_size` bytes at `off` lies within `bytes`.
+pub(crate) fn check_table(
+	bytes: &[u8],
+	size: u32,
+	off: u32,
+	item_size: usize,
+) -> Result<(), DexError> {
+	let start = off as usize;
+	let end = (size as usize)
+		.checked_mul(item_size)
+		.and_then(|len| len.checked_add(start));
+	match end {
+		Some(end) if end <= bytes.len() || size == 0 => Ok(()),
+		_ => Err(DexError::OffsetOutOfRange {
+			start,
+			end: end.unwrap_or(usize::MAX),
+			len: bytes.len(),
+		}),
+	}
+}
@@ -0,0 +1,38 @@
+//! Lowercase hexadecimal encoding of checksums and signatures, as printed by most other tools.
+
+/// Encodes bytes as a lowercase hex string, e.g. `[157, 28, 43, 122]` as `9d1c2b7a`.
+///
+/// # Examples
+///
+/// ```
+/// use dex_checksum_tools::hex;
+///
+/// assert_eq!(hex::encode(&[157, 28, 43, 122]), "9d1c2b7a");
+/// ```
+pub fn encode(bytes: &[u8]) -> String {
+	bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Decodes a hex string, in either case, into bytes.
+///
+/// # Returns
+/// - `Some(bytes)` if the string consists of an even number of hex digits.
+/// - `None` otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use dex_checksum_tools::hex;
+///
+/// assert_eq!(hex::decode("9D1C2B7A"), Some(vec![157, 28, 43, 122]));
+/// assert_eq!(hex::decode("9d1"), None);
+/// ```
+pub fn decode(hex: &str) -> Option<Vec<u8>> {
+	if hex.len() % 2 != 0 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+		return None;
+	}
+	(0..hex.len())
+		.step_by(2)
+		.map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+		.collect()
+}
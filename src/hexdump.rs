@@ -0,0 +1,98 @@
+//! Parsing and printing hex dumps of DEX files, as pasted into tickets.
+
+use std::fmt::Write;
+
+use crate::hex;
+use crate::DexError;
+
+/// The number of bytes on each line of a dump.
+const LINE_LEN: usize = 16;
+
+/// Parses a hex dump back into bytes.
+///
+/// Three layouts are understood, line by line:
+/// - `xxd`: `00000000: 6465 780a 3033 3500  dex.035.`, an offset ending with `:`, then the
+///   hex digits up to the two spaces in front of the ASCII column.
+/// - `hexdump -C`: `00000000  64 65 78 0a  |dex.|`, an offset, then the hex digits up to the
+///   `|` of the ASCII column. A `*` line repeats the previous line up to the next offset.
+/// - Plain hex digits, optionally separated by whitespace.
+pub(crate) fn parse(text: &str) -> Result<Vec<u8>, DexError> {
+	let mut out = Vec::new();
+	let mut previous = Vec::new();
+	let mut squeezed = false;
+	let mut canonical = false;
+	for (index, line) in text.lines().enumerate() {
+		let invalid = |what: &str| DexError::Decode(format!("line {}: {}", index + 1, what));
+		let line = line.trim();
+		if line.is_empty() {
+			continue;
+		}
+		if line == "*" {
+			squeezed = true;
+			continue;
+		}
+		let (offset, digits) = if let Some(bar) = line.find('|') {
+			canonical = true;
+			let (offset, digits) = line[..bar]
+				.trim()
+				.split_once(' ')
+				.unwrap_or((&line[..bar], ""));
+			(Some(offset), digits)
+		} else if canonical && !line.contains(' ') {
+			// `hexdump -C` ends with a line holding only the total length.
+			(Some(line), "")
+		} else if let Some((offset, rest)) = line.split_once(':') {
+			let rest = rest.trim_start();
+			(Some(offset), rest.split("  ").next().unwrap_or(""))
+		} else {
+			(None, line)
+		};
+		let offset = match offset {
+			Some(offset) => {
+				Some(usize::from_str_radix(offset, 16).map_err(|_| invalid("invalid offset"))?)
+			}
+			None => None,
+		};
+		let digits: String = digits.split_whitespace().collect();
+		let data = hex::decode(&digits).ok_or_else(|| invalid("invalid hex"))?;
+		if squeezed {
+			let offset = offset.ok_or_else(|| invalid("missing offset after `*`"))?;
+			while !previous.is_empty() && out.len() + previous.len() <= offset {
+				out.extend_from_slice(&previous);
+			}
+			squeezed = false;
+		}
+		if offset.is_some_and(|offset| offset != out.len()) {
+			return Err(invalid("offset does not follow the previous line"));
+		}
+		out.extend_from_slice(&data);
+		previous = data;
+	}
+	Ok(out)
+}
+
+/// Prints bytes as an `xxd` hex dump, which [`parse`] reads back.
+pub(crate) fn format(bytes: &[u8]) -> String {
+	let mut out = String::with_capacity(bytes.len() / LINE_LEN * 68 + 68);
+	for (index, line) in bytes.chunks(LINE_LEN).enumerate() {
+		let groups: Vec<String> = line.chunks(2).map(hex::encode).collect();
+		let ascii: String = line
+			.iter()
+			.map(|&b| {
+				if b.is_ascii_graphic() || b == b' ' {
+					b as char
+				} else {
+					'.'
+				}
+			})
+			.collect();
+		let _ = writeln!(
+			out,
+			"{:08x}: {:39}  {}",
+			index * LINE_LEN,
+			groups.join(" "),
+			ascii
+		);
+	}
+	out
+}
@@ -0,0 +1,99 @@
+use crate::checksum::checksum_of;
+use crate::ensure_not_zip;
+use crate::header::HEADER_SIZE;
+use crate::is_compact_dex_magic;
+use crate::is_container_dex_magic;
+use crate::is_dex_magic;
+use crate::sha1;
+use crate::DexError;
+
+/// Corrects the checksum of a DEX file held in a borrowed buffer, without copying it into a `Dex`.
+///
+/// This is meant for DEX files embedded in a larger buffer, such as an APK loaded into memory
+/// as a whole. The Adler-32 of `buf[12..]` is written to `buf[8..12]` in the byte order declared
+/// by the `endian_tag`.
+///
+/// # Arguments
+///
+/// * `buf` - The bytes of exactly one DEX file.
+///
+/// # Returns
+///
+/// `Ok(true)` if the checksum was wrong and a correction was applied, `Ok(false)` if it was
+/// already correct.
+///
+/// # Errors
+/// - Returns [`DexError::NotADex`] if `buf` holds a ZIP archive, such as an APK.
+/// - Returns [`DexError::UnsupportedFormat`] if `buf` holds a CompactDex file or a DEX container.
+/// - Returns [`DexError::TooShort`] if `buf` is shorter than the 0x70-byte header.
+/// - Returns [`DexError::BadMagic`] if `buf` does not start with a `dex\n???\0` magic.
+///
+/// # Examples
+///
+/// ```
+/// use dex_checksum_tools::correct_checksum_in_place;
+///
+/// let mut apk = std::fs::read("/path/to/input.apk").unwrap_or_default();
+/// if let Some(dex) = apk.get_mut(0x100..0x400) {
+///     match correct_checksum_in_place(dex) {
+///         Ok(corrected) => println!("corrected: {}", corrected),
+///         Err(e) => println!("Failed to correct checksum: {}", e),
+///     }
+/// }
+/// ```
+pub fn correct_checksum_in_place(buf: &mut [u8]) -> Result<bool, DexError> {
+	ensure_header(buf)?;
+	let expect = checksum_of(buf);
+	Ok(replace(&mut buf[8..12], &expect))
+}
+
+/// Corrects the SHA-1 signature of a DEX file held in a borrowed buffer, without copying it into a `Dex`.
+///
+/// The SHA-1 of `buf[32..]` is written to `buf[12..32]`. Since the signature is covered by the
+/// checksum, call [`correct_checksum_in_place`] afterwards.
+///
+/// # Arguments
+///
+/// * `buf` - The bytes of exactly one DEX file.
+///
+/// # Returns
+///
+/// `Ok(true)` if the signature was wrong and a correction was applied, `Ok(false)` if it was
+/// already correct.
+///
+/// # Errors
+/// - Returns [`DexError::NotADex`] if `buf` holds a ZIP archive, such as an APK.
+/// - Returns [`DexError::UnsupportedFormat`] if `buf` holds a CompactDex file or a DEX container.
+/// - Returns [`DexError::TooShort`] if `buf` is shorter than the 0x70-byte header.
+/// - Returns [`DexError::BadMagic`] if `buf` does not start with a `dex\n???\0` magic.
+pub fn correct_signature_in_place(buf: &mut [u8]) -> Result<bool, DexError> {
+	ensure_header(buf)?;
+	let expect = sha1::sha1(&buf[32..]);
+	Ok(replace(&mut buf[12..32], &expect))
+}
+
+fn ensure_header(buf: &[u8]) -> Result<(), DexError> {
+	ensure_not_zip(buf)?;
+	if is_compact_dex_magic(buf) || is_container_dex_magic(buf) {
+		Err(DexError::UnsupportedFormat(buf[0..8].try_into().unwrap()))
+	} else if buf.len() < HEADER_SIZE {
+		Err(DexError::TooShort {
+			needed: HEADER_SIZE,
+			got: buf.len(),
+		})
+	} else if !is_dex_magic(buf) {
+		Err(DexError::BadMagic(buf[0..8].try_into().unwrap()))
+	} else {
+		Ok(())
+	}
+}
+
+/// Overwrites `field` with `expect`, returning whether it differed.
+fn replace(field: &mut [u8], expect: &[u8]) -> bool {
+	if field != expect {
+		field.copy_from_slice(expect);
+		true
+	} else {
+		false
+	}
+}
@@ -0,0 +1,39 @@
+use std::ops::Range;
+
+/// Where the Adler-32 checksum of a DEX-like file lives, and which bytes it covers.
+///
+/// Standard DEX files use [`DEX_LAYOUT`]. Variant formats that keep the checksum field or the
+/// checksummed body elsewhere can describe them with their own layout, and use
+/// [`crate::Dex::correct_checksum_with_layout`].
+///
+/// # Fields
+/// * `field` - The 4 bytes holding the checksum, written according to the file's `endian_tag`.
+/// * `body` - The bytes covered by the checksum. The end is clamped to the length of the file,
+///   so `usize::MAX` means "up to the end of the file".
+///
+/// # Examples
+///
+/// ```
+/// use dex_checksum_tools::ChecksumLayout;
+/// use dex_checksum_tools::Dex;
+///
+/// // A variant that keeps its checksum right after the magic, covering the rest of the file.
+/// let layout = ChecksumLayout {
+///     field: 8..12,
+///     body: 0x70..usize::MAX,
+/// };
+/// let mut dex = Dex::empty(35);
+/// assert_eq!(dex.correct_checksum_with_layout(&layout).unwrap(), true);
+/// assert_eq!(dex.check_checksum_with_layout(&layout).unwrap(), true);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChecksumLayout {
+	pub field: Range<usize>,
+	pub body: Range<usize>,
+}
+
+/// The layout of standard DEX files: the checksum at `8..12`, covering everything from 12 on.
+pub const DEX_LAYOUT: ChecksumLayout = ChecksumLayout {
+	field: 8..12,
+	body: 12..usize::MAX,
+};
@@ -1,20 +1,131 @@
-use adler32::adler32;
 use std::fmt::Display;
+#[cfg(feature = "std-fs")]
 use std::fs::File;
 use std::io;
 use std::io::{Read, Write};
+use std::ops::Range;
+#[cfg(feature = "std-fs")]
+use std::path::{Path, PathBuf};
+
+use bytes::Bytes;
+
+pub use checksum::{check_checksum_slice, checksum_matches_for, checksum_of, verify_against};
+pub use digests::Digests;
+pub use error::DexError;
+pub use header::DexHeader;
+pub use in_place::{correct_checksum_in_place, correct_signature_in_place};
+pub use layout::{ChecksumLayout, DEX_LAYOUT};
+pub use map::MapItem;
+pub use stream::verify_checksum_streaming;
+pub use version::DexVersion;
+
+mod adler;
+pub mod apk;
+#[cfg(feature = "async")]
+pub mod async_read;
+#[cfg(feature = "base64")]
+mod base64;
+#[cfg(feature = "std-fs")]
+pub mod batch;
+mod bytes;
+mod checksum;
+mod crc32;
+mod deflate;
+mod digests;
+mod error;
+#[cfg(feature = "gzip")]
+mod gzip;
+mod header;
+pub mod hex;
+mod hexdump;
+mod in_place;
+mod layout;
+mod map;
+#[cfg(all(feature = "memmap", unix))]
+mod mmap;
+mod sha1;
+#[cfg(feature = "sha2")]
+mod sha256;
+mod stream;
+mod strings;
+mod version;
+mod zip;
+
+/// The checksum, compression and encoding algorithms this crate implements itself, exposed for
+/// its own known-answer tests under `tests/`. Not part of the API: they may change at any time.
+#[doc(hidden)]
+pub mod primitives {
+	pub use crate::crc32::crc32;
+	pub use crate::deflate::{deflate, inflate, inflate_limited};
+	pub use crate::sha1::{sha1, Sha1};
+	#[cfg(feature = "sha2")]
+	pub use crate::sha256::{sha256, Sha256};
+	#[cfg(feature = "base64")]
+	pub mod base64 {
+		pub use crate::base64::decode;
+	}
+	#[cfg(feature = "gzip")]
+	pub mod gzip {
+		pub use crate::gzip::{compress, decompress, is_gzip};
+	}
+}
+
+/// The byte order of a DEX file, as declared by its `endian_tag` header field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endianness {
+	/// The standard little-endian layout, with `endian_tag` `0x12345678`.
+	Little,
+	/// The reverse-endian layout, with `endian_tag` `0x78563412`.
+	Big,
+}
+
+impl Endianness {
+	/// Reads the `endian_tag` of a raw DEX buffer, defaulting to little-endian when it is absent.
+	pub(crate) fn of(bytes: &[u8]) -> Endianness {
+		match bytes.get(40..44) {
+			Some([0x12, 0x34, 0x56, 0x78]) => Endianness::Big,
+			_ => Endianness::Little,
+		}
+	}
+
+	pub(crate) fn decode_u16(self, bytes: [u8; 2]) -> u16 {
+		match self {
+			Endianness::Little => u16::from_le_bytes(bytes),
+			Endianness::Big => u16::from_be_bytes(bytes),
+		}
+	}
+
+	pub(crate) fn decode_u32(self, bytes: [u8; 4]) -> u32 {
+		match self {
+			Endianness::Little => u32::from_le_bytes(bytes),
+			Endianness::Big => u32::from_be_bytes(bytes),
+		}
+	}
+
+	pub(crate) fn encode_u32(self, value: u32) -> [u8; 4] {
+		match self {
+			Endianness::Little => value.to_le_bytes(),
+			Endianness::Big => value.to_be_bytes(),
+		}
+	}
+}
 
 /// A `Dex` structure that holds the bytes of a DEX (Dalvik Executable) file.
 ///
 /// # Fields
 /// * `bytes` - A vector of bytes representing the contents of the DEX file.
+/// * `source` - The path the DEX file was read from, if it was opened from a path, so that
+///   it can be [reloaded](Dex::reload).
+///
+/// Two `Dex` values are equal if their bytes are, wherever they were read from.
 ///
 /// # Examples
 ///
 /// ```
 /// use dex_checksum_tools::Dex;
 ///
-/// if let Ok(mut dex) = Dex::try_from("/path/to/incorrect.dex") {
+/// # #[cfg(feature = "std-fs")]
+/// if let Ok(mut dex) = Dex::open("/path/to/incorrect.dex") {
 ///     println!("Before Correcting: current_checksum {:?}", dex.current_checksum());
 ///     println!("Before Correcting: check_checksum {:?}", dex.check_checksum());
 ///     println!("Before Correcting: expect_checksum {:?}", dex.expect_checksum());
@@ -28,133 +139,1789 @@ use std::io::{Read, Write};
 ///     }
 /// }
 /// ````
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Dex {
-	bytes: Vec<u8>,
+	bytes: Bytes,
+	#[cfg(feature = "std-fs")]
+	source: Option<PathBuf>,
 }
 
 impl Dex {
-	/// Calculates the current checksum from the DEX file's header.
+	/// Creates a `Dex` from the bytes of a DEX file that are already in memory.
 	///
-	/// This method extracts the checksum bytes that are stored at offset 8 through 11 in the DEX file header
-	/// and converts them into a 4-byte array. The checksum is a part of the file's integrity verification
-	/// and should match the expected checksum calculated over the rest of the file.
+	/// # Arguments
 	///
-	/// # Returns
-	/// A 4-byte array representing the checksum stored in the DEX file header.
+	/// * `bytes` - A vector of bytes representing the contents of the DEX file.
 	///
-	/// # Panics
-	/// Panics if the slice of bytes cannot be converted into an array, which indicates an issue with the DEX file format.
-	pub fn current_checksum(&self) -> [u8; 4] {
-		self.bytes[8..12]
-			.try_into()
-			.expect("Could not convert slice to array!")
+	/// # Examples
+	///
+	/// ```
+	/// use dex_checksum_tools::Dex;
+	///
+	/// let bytes = std::fs::read("/path/to/input.dex").unwrap_or_default();
+	/// let dex = Dex::from_bytes(bytes);
+	/// ```
+	pub fn from_bytes(bytes: Vec<u8>) -> Dex {
+		Dex {
+			bytes: Bytes::Owned(bytes),
+			#[cfg(feature = "std-fs")]
+			source: None,
+		}
 	}
 
-	/// Calculates the expected checksum for the DEX file.
+	/// Creates a `Dex` with no bytes at all, for a producer to write a whole DEX file into
+	/// through its [`Write`] implementation.
 	///
-	/// This method computes the Adler-32 checksum for the data part of the DEX file
-	/// starting from byte 12 to the end of the file. The Adler-32 checksum is used
-	/// for error-checking of the data. It should match the current checksum in the
-	/// file header for the file to be considered valid.
+	/// To append to a valid header and `map_list` instead, start from [`Dex::empty`].
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::io::Write;
+	///
+	/// use dex_checksum_tools::Dex;
+	///
+	/// let mut dex = Dex::new();
+	/// dex.write_all(Dex::empty(35).as_bytes()).unwrap();
+	/// assert_eq!(dex, Dex::empty(35));
+	/// ```
+	pub fn new() -> Dex {
+		Dex::from_bytes(Vec::new())
+	}
+
+	/// Creates a `Dex` by copying the given slice of bytes.
+	///
+	/// # Arguments
+	///
+	/// * `bytes` - A slice of bytes representing the contents of the DEX file.
+	pub fn from_slice(bytes: &[u8]) -> Dex {
+		Dex::from_bytes(bytes.to_vec())
+	}
+
+	/// Creates a `Dex` by reading all bytes from the given reader until EOF.
+	///
+	/// # Arguments
+	///
+	/// * `reader` - Any source implementing `Read`, such as a `File`, a `Cursor` or stdin.
 	///
 	/// # Returns
-	/// A 4-byte array representing the expected checksum for the DEX file.
+	///
+	/// An `io::Result<Dex>` which is `Ok` if the reader was read to the end successfully,
+	/// or an `Err` with more information if reading failed.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::io::Cursor;
+	///
+	/// use dex_checksum_tools::Dex;
+	///
+	/// let bytes = Dex::empty(35).into_bytes();
+	/// let dex = Dex::from_reader(Cursor::new(bytes)).unwrap();
+	/// assert!(dex.check_checksum());
+	/// ```
+	///
+	/// # Gzip
+	///
+	/// With the `gzip` feature, input starting with the gzip magic `1f 8b` is decompressed
+	/// transparently, so `classes.dex.gz` can be read like `classes.dex`. This also applies to
+	/// the `TryFrom` implementations for paths and `File`. Use [`Dex::write_gzip_to`] to
+	/// compress it again after a correction.
+	///
+	/// # Async runtimes
+	///
+	/// This method blocks. From an async runtime, use [`Dex::from_async_reader`] with the
+	/// `async` feature, or read the bytes with the runtime's own I/O (e.g. `tokio::fs::read`)
+	/// and pass them to [`Dex::from_bytes`]. The checksum and signature computations are
+	/// CPU-bound and can then run synchronously.
+	pub fn from_reader<R: Read>(mut reader: R) -> io::Result<Dex> {
+		let mut bytes = Vec::<u8>::new();
+		reader.read_to_end(&mut bytes)?;
+		#[cfg(feature = "gzip")]
+		if gzip::is_gzip(&bytes) {
+			bytes = gzip::decompress(&bytes)?;
+		}
+		Ok(Dex::from_bytes(bytes))
+	}
+
+	/// Creates a `Dex` by reading an [`AsyncRead`](async_read::AsyncRead) to its end, like
+	/// [`Dex::from_reader`] but without blocking the async runtime.
+	///
+	/// Only the reading is awaitable: the checksum and signature computations are CPU-bound
+	/// and stay synchronous. Gzip input is decompressed like in [`Dex::from_reader`].
+	///
+	/// Only available with the `async` feature.
+	///
+	/// # Arguments
+	///
+	/// * `reader` - The source of the DEX file bytes, such as a file of any async runtime
+	///   wrapped in an [`AsyncRead`](async_read::AsyncRead) implementation.
 	///
 	/// # Errors
-	/// Returns an error if the Adler-32 checksum cannot be calculated.
-	pub fn expect_checksum(&self) -> [u8; 4] {
-		let mut hash = adler32(&self.bytes[12..]).expect("Unable to calculate adler32 checksum!");
-		let mut buffer: [u8; 4] = [0; 4];
-		for i in (0..4).rev() {
-			buffer[i] = (hash % 256) as u8;
-			hash >>= 8;
+	/// Returns the first error of the reader, other than `io::ErrorKind::Interrupted`.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use dex_checksum_tools::Dex;
+	///
+	/// async fn is_valid(bytes: &[u8]) -> std::io::Result<bool> {
+	///     let dex = Dex::from_async_reader(bytes).await?;
+	///     Ok(dex.check_checksum())
+	/// }
+	/// ```
+	#[cfg(feature = "async")]
+	pub async fn from_async_reader<R: async_read::AsyncRead + Unpin>(reader: R) -> io::Result<Dex> {
+		let bytes = async_read::read_to_end(reader).await?;
+		#[cfg(feature = "gzip")]
+		if gzip::is_gzip(&bytes) {
+			return Ok(Dex::from_bytes(gzip::decompress(&bytes)?));
 		}
-		buffer.reverse();
-		buffer
+		Ok(Dex::from_bytes(bytes))
 	}
 
-	/// Checks if the current checksum matches the expected checksum.
+	/// Creates a `Dex` by memory-mapping the file at the specified path read-only.
 	///
-	/// This method compares the current checksum from the file's header
-	/// with the expected checksum calculated over the data part of the file.
+	/// Verifying the checksum or signature of a mapped file does not copy it onto the heap.
+	/// Correcting it copies the bytes into an owned buffer first, so the file on disk is
+	/// never modified through the mapping. The file must not be modified by another process
+	/// while it is mapped.
+	///
+	/// Only available on Unix with the `memmap` feature.
+	///
+	/// # Arguments
+	///
+	/// * `path` - The path of the file to map.
 	///
 	/// # Returns
-	/// - `true` if the checksums match.
-	/// - `false` otherwise.
-	pub fn check_checksum(&self) -> bool {
-		self.current_checksum() == self.expect_checksum()
+	///
+	/// An `io::Result<Dex>` which is `Ok` if the file was mapped successfully,
+	/// or an `Err` with more information if the file could not be opened or mapped.
+	#[cfg(all(feature = "memmap", unix))]
+	pub fn from_mmap<P: AsRef<Path>>(path: P) -> io::Result<Dex> {
+		let file = File::open(&path)?;
+		let len = file.metadata()?.len() as usize;
+		let bytes = match len {
+			0 => Bytes::Owned(Vec::new()),
+			len => Bytes::Mapped(mmap::Mmap::map(&file, len)?),
+		};
+		Ok(Dex {
+			bytes,
+			source: Some(path.as_ref().to_path_buf()),
+		})
 	}
 
-	/// Corrects the checksum in the DEX file header if it does not match the expected checksum.
-	///
-	/// This method calculates the expected checksum using the `expect_checksum` method
-	/// and updates the bytes in the DEX file header if the current checksum is incorrect.
-	/// After calling this method, the checksum in the file header should match the
-	/// expected checksum for the data part of the DEX file.
+	/// Returns the path the DEX file was read from.
 	///
 	/// # Returns
-	/// - `true` if the checksum was uncorrected.
-	/// - `false` otherwise.
-	pub fn correct_checksum(&mut self) -> bool {
-		let expect = self.expect_checksum();
-		if self.current_checksum() != expect {
-			self.bytes[8..12].copy_from_slice(&expect);
-			true
-		} else {
-			false
+	/// - `Some(path)` if it was opened from a path, such as with `Dex::open("classes.dex")`.
+	/// - `None` if it was built from bytes or a reader.
+	#[cfg(feature = "std-fs")]
+	pub fn source_path(&self) -> Option<&Path> {
+		self.source.as_deref()
+	}
+
+	/// Reads the DEX file again from the path it was read from, replacing the bytes in memory
+	/// and any correction made to them.
+	///
+	/// This lets long-lived tools, such as a file watcher, refresh a `Dex` after another
+	/// process rewrote the file. A memory-mapped file is mapped again.
+	///
+	/// # Errors
+	/// - An [`io::ErrorKind::InvalidInput`] error if the `Dex` was not read from a path, see
+	///   [`Dex::source_path`].
+	/// - Any error from reading the file. The bytes are left untouched then.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use dex_checksum_tools::Dex;
+	///
+	/// if let Ok(mut dex) = Dex::open("/path/to/input.dex") {
+	///     // ... the file is rewritten by another process ...
+	///     match dex.reload() {
+	///         Ok(_) => println!("checksum valid: {}", dex.check_checksum()),
+	///         Err(e) => println!("Failed to reload: {}", e),
+	///     }
+	/// }
+	///
+	/// assert!(Dex::empty(35).reload().is_err());
+	/// ```
+	#[cfg(feature = "std-fs")]
+	pub fn reload(&mut self) -> io::Result<()> {
+		let path = self.source.clone().ok_or_else(|| {
+			io::Error::new(
+				io::ErrorKind::InvalidInput,
+				"DEX file was not read from a path, there is nothing to reload",
+			)
+		})?;
+		#[cfg(all(feature = "memmap", unix))]
+		if matches!(self.bytes, Bytes::Mapped(_)) {
+			*self = Dex::from_mmap(path)?;
+			return Ok(());
 		}
+		*self = Dex::open(path)?;
+		Ok(())
 	}
 
-	/// Writes the DEX file's bytes to the specified path.
+	/// Opens and reads the DEX file at the specified path.
 	///
-	/// This function creates a new file at the given `path` and writes the
-	/// bytes representing the DEX file to it. If the file already exists,
-	/// it will be truncated before writing.
+	/// This is the same as `Dex::try_from(path)` with a `&str` or `String`, but cannot be
+	/// mistaken for building a `Dex` from its contents, which [`Dex::from_bytes`] and
+	/// [`Dex::try_from_bytes`] do. The path is remembered for [`Dex::source_path`] and
+	/// [`Dex::reload`].
 	///
 	/// # Arguments
 	///
-	/// * `path` - A string slice that holds the path where the file will be written.
+	/// * `path` - The path of the DEX file to read.
 	///
 	/// # Returns
 	///
-	/// An `io::Result<()>` which is `Ok` if the file was written successfully,
-	/// or an `Err` with more information if the file could not be written.
-	pub fn write_to_file(&self, path: &str) -> io::Result<()> {
-		File::create(path)?.write_all(&self.bytes)
+	/// An `io::Result<Dex>` which is `Ok` if the file was read successfully,
+	/// or an `Err` with more information if the file could not be opened or read.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use dex_checksum_tools::Dex;
+	///
+	/// match Dex::open("/path/to/input.dex") {
+	///     Ok(dex) => println!("checksum valid: {}", dex.check_checksum()),
+	///     Err(e) => println!("Failed to read: {}", e),
+	/// }
+	/// ```
+	#[cfg(feature = "std-fs")]
+	pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Dex> {
+		let path = path.as_ref();
+		let mut dex = Dex::try_from(File::open(path)?)?;
+		dex.source = Some(path.to_path_buf());
+		Ok(dex)
 	}
-}
 
-impl Display for Dex {
-	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		write!(f, "Dex {{ bytes: {:?} }}", self.bytes)
+	/// Creates a `Dex` from in-memory bytes, validating that they look like a DEX file.
+	///
+	/// Unlike [`Dex::from_bytes`], this constructor checks that the bytes start with a
+	/// `dex\n???\0` magic, so that arbitrary files are rejected up front.
+	///
+	/// # Arguments
+	///
+	/// * `bytes` - A vector of bytes representing the contents of the DEX file.
+	///
+	/// # Errors
+	/// - [`DexError::NotADex`] if the bytes are a ZIP archive, such as an APK.
+	/// - [`DexError::TooShort`] if there are fewer than 8 bytes.
+	/// - [`DexError::UnsupportedFormat`] if the magic is a CompactDex magic.
+	/// - [`DexError::BadMagic`] if the magic is not a DEX magic.
+	pub fn try_from_bytes(bytes: Vec<u8>) -> Result<Dex, DexError> {
+		ensure_not_zip(&bytes)?;
+		let dex = Dex::from_bytes(bytes);
+		dex.ensure_len(8)?;
+		if dex.is_compact_dex() {
+			return Err(DexError::UnsupportedFormat(dex.magic()));
+		}
+		if !dex.is_valid_magic() {
+			return Err(DexError::BadMagic(dex.magic()));
+		}
+		Ok(dex)
 	}
-}
 
-impl TryFrom<File> for Dex {
-	type Error = io::Error;
+	/// Creates a `Dex` from base64-encoded bytes, such as DEX data pasted into a bug report.
+	///
+	/// Whitespace, including line wrapping, is ignored, and the trailing `=` padding is optional.
+	///
+	/// Only available with the `base64` feature.
+	///
+	/// # Arguments
+	///
+	/// * `text` - The standard (RFC 4648) base64 encoding of the contents of the DEX file.
+	///
+	/// # Errors
+	/// - [`DexError::Decode`] if `text` is not valid base64.
+	/// - [`DexError::NotADex`] if the decoded bytes are a ZIP archive, such as an APK.
+	/// - [`DexError::UnsupportedFormat`] if the decoded bytes start with a CompactDex magic.
+	/// - [`DexError::BadMagic`] if the decoded bytes do not start with a DEX magic.
+	/// - [`DexError::TooShort`] if the decoded bytes cannot hold a 0x70-byte header.
+	#[cfg(feature = "base64")]
+	pub fn from_base64(text: &str) -> Result<Dex, DexError> {
+		let bytes =
+			base64::decode(text).ok_or_else(|| DexError::Decode("invalid base64".to_string()))?;
+		Dex::try_from(bytes)
+	}
 
-	fn try_from(mut f: File) -> Result<Self, Self::Error> {
-		let mut bytes = Vec::<u8>::new();
-		f.read_to_end(&mut bytes).map(|_| Dex { bytes })
+	/// Creates a `Dex` from a hex dump, such as the output of `xxd` or `hexdump -C`.
+	///
+	/// Offsets and ASCII columns are ignored, and plain hex digits are accepted too. Use
+	/// [`Dex::to_hexdump`] to print the bytes again once corrected.
+	///
+	/// # Arguments
+	///
+	/// * `text` - The hex dump of the contents of the DEX file.
+	///
+	/// # Errors
+	/// - [`DexError::Decode`] if a line is not valid hex, or its offset does not follow from the
+	///   lines before it.
+	/// - [`DexError::NotADex`] if the decoded bytes are a ZIP archive, such as an APK.
+	/// - [`DexError::UnsupportedFormat`] if the decoded bytes start with a CompactDex magic.
+	/// - [`DexError::BadMagic`] if the decoded bytes do not start with a DEX magic.
+	/// - [`DexError::TooShort`] if the decoded bytes cannot hold a 0x70-byte header.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use dex_checksum_tools::Dex;
+	///
+	/// let mut bytes = Dex::empty(35).into_bytes();
+	/// bytes[8] ^= 0xff;
+	/// let mut dex = Dex::from_hexdump(&Dex::from_bytes(bytes).to_hexdump()).unwrap();
+	/// assert!(dex.correct_checksum());
+	/// assert_eq!(dex, Dex::empty(35));
+	/// ```
+	pub fn from_hexdump(text: &str) -> Result<Dex, DexError> {
+		Dex::try_from(hexdump::parse(text)?)
 	}
-}
 
-impl TryFrom<String> for Dex {
-	type Error = io::Error;
+	/// Creates a minimal, structurally valid DEX file with no strings, types or classes.
+	///
+	/// The file consists of the 0x70-byte header, with every id table empty, followed by a
+	/// `map_list` describing the header and itself. The magic, `endian_tag`, `file_size`,
+	/// `header_size`, signature and checksum are all filled in, which makes it useful as a
+	/// fixture.
+	///
+	/// # Arguments
+	///
+	/// * `version` - The DEX format version to write into the magic, e.g. `35` for `dex\n035\0`.
+	///
+	/// # Panics
+	/// Panics if `version` does not fit in three decimal digits.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use dex_checksum_tools::Dex;
+	///
+	/// let dex = Dex::empty(35);
+	/// assert_eq!(dex.version(), Some(35));
+	/// assert!(dex.check_checksum() && dex.check_signature() && dex.check_file_size());
+	/// ```
+	pub fn empty(version: u32) -> Dex {
+		assert!(
+			version <= 999,
+			"DEX version {} does not fit the magic!",
+			version
+		);
+		const MAP_OFF: u32 = header::HEADER_SIZE as u32;
+		// A map_list of 2 items: the header_item and the map_list itself.
+		const MAP_SIZE: u32 = 4 + 2 * 12;
+		let mut bytes = Vec::with_capacity((MAP_OFF + MAP_SIZE) as usize);
+		bytes.extend_from_slice(format!("dex\n{:03}\0", version).as_bytes());
+		bytes.resize(32, 0);
+		for field in [
+			MAP_OFF + MAP_SIZE, // file_size
+			MAP_OFF,            // header_size
+			0x12345678,         // endian_tag
+			0,                  // link_size
+			0,                  // link_off
+			MAP_OFF,            // map_off
+		] {
+			bytes.extend_from_slice(&field.to_le_bytes());
+		}
+		bytes.resize(104, 0);
+		bytes.extend_from_slice(&MAP_SIZE.to_le_bytes()); // data_size
+		bytes.extend_from_slice(&MAP_OFF.to_le_bytes()); // data_off
 
-	fn try_from(path: String) -> Result<Self, Self::Error> {
-		match File::open(path) {
-			Ok(f) => Dex::try_from(f),
-			Err(e) => Err(e),
+		// The item count, then the type, size and offset of each item.
+		for word in [2, 0x0000, 1, 0, 0x1000, 1, MAP_OFF] {
+			bytes.extend_from_slice(&word.to_le_bytes());
 		}
+		let mut dex = Dex::from_bytes(bytes);
+		dex.correct_all();
+		dex
 	}
-}
 
-impl TryFrom<&str> for Dex {
-	type Error = io::Error;
+	/// Borrows the bytes of the DEX file.
+	///
+	/// # Returns
+	/// A slice of the (possibly corrected) contents of the DEX file.
+	pub fn as_bytes(&self) -> &[u8] {
+		&self.bytes
+	}
 
-	fn try_from(path: &str) -> Result<Self, Self::Error> {
-		Dex::try_from(String::from(path))
+	/// Consumes the `Dex` and returns the bytes of the DEX file.
+	///
+	/// # Returns
+	/// A vector of the (possibly corrected) contents of the DEX file.
+	pub fn into_bytes(self) -> Vec<u8> {
+		self.bytes.into_vec()
+	}
+
+	/// Returns the length of the DEX file in bytes.
+	///
+	/// Unlike the `file_size` header field, this is the actual number of bytes held, see
+	/// [`Dex::check_file_size`].
+	pub fn len(&self) -> usize {
+		self.bytes.len()
+	}
+
+	/// Returns `true` if the DEX file holds no bytes at all.
+	pub fn is_empty(&self) -> bool {
+		self.bytes.is_empty()
+	}
+
+	/// Extracts the magic from the DEX file's header.
+	///
+	/// A valid DEX file starts with the magic `dex\n` followed by a three-digit version
+	/// and a zero byte, e.g. `dex\n035\0`.
+	///
+	/// # Returns
+	/// An 8-byte array representing the magic stored at offset 0 through 7.
+	///
+	/// # Panics
+	/// Panics if the slice of bytes cannot be converted into an array, which indicates an issue with the DEX file format.
+	pub fn magic(&self) -> [u8; 8] {
+		self.bytes[0..8]
+			.try_into()
+			.expect("Could not convert slice to array!")
+	}
+
+	/// Parses the DEX format version from the magic, e.g. `35` for `dex\n035\0`.
+	///
+	/// # Returns
+	/// - `Some(version)` if the magic is a valid DEX magic.
+	/// - `None` otherwise, including for CompactDex files.
+	pub fn version(&self) -> Option<u32> {
+		if !self.is_valid_magic() {
+			return None;
+		}
+		std::str::from_utf8(&self.bytes[4..7]).ok()?.parse().ok()
+	}
+
+	/// Parses the DEX format version from the magic as a [`DexVersion`].
+	///
+	/// # Returns
+	/// - `Some(version)` if the magic is a valid DEX magic.
+	/// - `None` otherwise, including for CompactDex files.
+	pub fn dex_version(&self) -> Option<DexVersion> {
+		self.version().map(DexVersion::from)
+	}
+
+	/// Checks if the file starts with the `cdex???\0` magic of an ART CompactDex file.
+	///
+	/// CompactDex files use a different header layout and checksum scheme, which this crate
+	/// does not support. [`Dex::version`] returns `None` for them, and the fallible
+	/// corrections refuse them with [`DexError::UnsupportedFormat`].
+	///
+	/// # Returns
+	/// - `true` if the magic is a CompactDex magic.
+	/// - `false` otherwise, including when the file is shorter than 8 bytes.
+	pub fn is_compact_dex(&self) -> bool {
+		is_compact_dex_magic(&self.bytes)
+	}
+
+	/// Checks if the file is a DEX container of version 041 or later, in which several DEX
+	/// files share one file and one data section.
+	///
+	/// Each DEX file of a container has its own header, whose checksum and signature only cover
+	/// that DEX file rather than the whole file. Correcting them as if the container were one
+	/// DEX file would corrupt it, so the fallible corrections refuse containers with
+	/// [`DexError::UnsupportedFormat`].
+	///
+	/// # Returns
+	/// - `true` if the magic is a valid DEX magic with a version of 41 or later.
+	/// - `false` otherwise.
+	pub fn is_container_dex(&self) -> bool {
+		is_container_dex_magic(&self.bytes)
+	}
+
+	/// Checks if the DEX file starts with a valid `dex\n???\0` magic.
+	///
+	/// # Returns
+	/// - `true` if the magic is valid.
+	/// - `false` otherwise, including when the file is shorter than 8 bytes.
+	pub fn is_valid_magic(&self) -> bool {
+		is_dex_magic(&self.bytes)
+	}
+
+	/// Determines the byte order of the DEX file from the `endian_tag` header field.
+	///
+	/// The `endian_tag` at offset 40 is `0x12345678` for little-endian files and
+	/// `0x78563412` for reverse-endian files.
+	///
+	/// # Returns
+	/// - `Endianness::Big` if the tag indicates a reverse-endian file.
+	/// - `Endianness::Little` otherwise, including when the file is too short to contain the tag.
+	pub fn endianness(&self) -> Endianness {
+		Endianness::of(&self.bytes)
+	}
+
+	/// Checks that the DEX file is long enough to hold a complete 0x70-byte header.
+	///
+	/// Every fallible accessor calls this before slicing into the header, so that arbitrary
+	/// or truncated input yields a clean error instead of an index-out-of-bounds panic.
+	///
+	/// # Errors
+	/// Returns [`DexError::TooShort`] if the DEX file is shorter than the 0x70-byte header.
+	pub fn validate_minimum_length(&self) -> Result<(), DexError> {
+		self.ensure_len(header::HEADER_SIZE)
+	}
+
+	/// The byte range covered by the checksum: from offset 12, just past the checksum field,
+	/// to the end of the file.
+	///
+	/// # Returns
+	/// The range of bytes fed into the Adler-32.
+	pub fn checksum_region(&self) -> Range<usize> {
+		12..self.bytes.len()
+	}
+
+	/// The byte range covered by the signature: from offset 32, just past the signature field,
+	/// to the end of the file.
+	///
+	/// # Returns
+	/// The range of bytes fed into the SHA-1.
+	pub fn signature_region(&self) -> Range<usize> {
+		32..self.bytes.len()
+	}
+
+	/// Iterates over the string pool, in the order of the string identifiers list.
+	///
+	/// Each entry of the list points at a ULEB128-prefixed MUTF-8 string in the data section,
+	/// which is decoded lazily as the iterator advances. This is useful for light inspection,
+	/// e.g. to confirm that a renamed class is present after a patch.
+	///
+	/// # Returns
+	/// An iterator yielding each string, or the error that prevented decoding it.
+	///
+	/// # Errors
+	/// - Returns [`DexError::TooShort`] if the DEX file is shorter than the 0x70-byte header.
+	/// - Returns [`DexError::OffsetOutOfRange`] if the string identifiers list lies outside of the file.
+	/// - The iterator yields [`DexError::OffsetOutOfRange`] if a string lies outside of the file.
+	/// - The iterator yields [`DexError::InvalidString`] if a string is not well-formed MUTF-8.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use dex_checksum_tools::Dex;
+	///
+	/// # #[cfg(feature = "std-fs")]
+	/// if let Ok(dex) = Dex::open("/path/to/input.dex") {
+	///     if let Ok(strings) = dex.strings() {
+	///         let found = strings.flatten().any(|s| s == "Lcom/example/Renamed;");
+	///         println!("renamed class present: {}", found);
+	///     }
+	/// }
+	/// ```
+	pub fn strings(&self) -> Result<impl Iterator<Item = Result<String, DexError>> + '_, DexError> {
+		let header = self.header()?;
+		header::check_table(
+			&self.bytes,
+			header.string_ids_size(),
+			header.string_ids_off(),
+			4,
+		)?;
+		let endianness = self.endianness();
+		Ok((0..header.string_ids_size()).map(move |index| {
+			strings::string_at(&self.bytes, endianness, header.string_ids_off(), index)
+		}))
+	}
+
+	/// Decodes the full DEX file header.
+	///
+	/// # Returns
+	/// A [`DexHeader`] holding every header field, decoded according to [`Dex::endianness`].
+	///
+	/// # Errors
+	/// Returns [`DexError::TooShort`] if the DEX file is shorter than the 0x70-byte header.
+	pub fn header(&self) -> Result<DexHeader, DexError> {
+		DexHeader::parse(&self.bytes, self.endianness())
+	}
+
+	/// Checks that every section declared in the header lies within the file.
+	///
+	/// The sizes and offsets of the id tables, class definitions, `link` and `data` sections
+	/// are all read from the header, so a malformed or malicious file can point them anywhere.
+	/// Every bound is computed with checked arithmetic.
+	///
+	/// # Errors
+	/// - Returns [`DexError::TooShort`] if the DEX file is shorter than the 0x70-byte header.
+	/// - Returns [`DexError::OffsetOutOfRange`] for the first section that lies outside of the file.
+	pub fn validate_sections(&self) -> Result<(), DexError> {
+		self.header()?.validate_sections(&self.bytes)
+	}
+
+	/// Decodes the `map_list` that the `map_off` header field points to.
+	///
+	/// The `map_list` is the authoritative table of every section in the file, so it can be
+	/// used to check that section offsets are still consistent after a patch.
+	///
+	/// # Returns
+	/// The [`MapItem`] of every section, in the order they are listed.
+	///
+	/// # Errors
+	/// - Returns [`DexError::TooShort`] if the DEX file is shorter than the 0x70-byte header.
+	/// - Returns [`DexError::OffsetOutOfRange`] if the `map_list` lies outside of the file.
+	pub fn map_list(&self) -> Result<Vec<MapItem>, DexError> {
+		let header = self.header()?;
+		MapItem::parse_list(&self.bytes, self.endianness(), header.map_off())
+	}
+
+	/// Checks that the `map_list` describes the layout of the file consistently.
+	///
+	/// A file with a correct checksum and signature can still have a shuffled or overlapping
+	/// `map_list`, which the Android verifier rejects. This checks that:
+	/// - the header is the first section, at offset 0, and each section is listed once;
+	/// - the sections are listed in increasing order of offset, and sections of fixed-size
+	///   items, such as the id tables, do not overlap the next section;
+	/// - the `map_list` is the last section, at `map_off`, and ends the data section.
+	///
+	/// # Returns
+	/// - `Ok(true)` if the `map_list` is consistent.
+	/// - `Ok(false)` otherwise.
+	///
+	/// # Errors
+	/// See [`Dex::map_list`].
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use dex_checksum_tools::Dex;
+	///
+	/// assert_eq!(Dex::empty(35).check_map_consistency().ok(), Some(true));
+	/// ```
+	pub fn check_map_consistency(&self) -> Result<bool, DexError> {
+		let header = self.header()?;
+		let items = MapItem::parse_list(&self.bytes, self.endianness(), header.map_off())?;
+		Ok(MapItem::is_consistent_list(&items, &header))
+	}
+
+	/// Calculates the current checksum from the DEX file's header.
+	///
+	/// This method extracts the checksum bytes that are stored at offset 8 through 11 in the DEX file header
+	/// and converts them into a 4-byte array. The checksum is a part of the file's integrity verification
+	/// and should match the expected checksum calculated over the rest of the file.
+	///
+	/// # Returns
+	/// A 4-byte array representing the checksum stored in the DEX file header.
+	///
+	/// # Panics
+	/// Panics if the slice of bytes cannot be converted into an array, which indicates an issue with the DEX file format.
+	pub fn current_checksum(&self) -> [u8; 4] {
+		self.bytes[8..12]
+			.try_into()
+			.expect("Could not convert slice to array!")
+	}
+
+	/// Calculates the current checksum from the DEX file's header without panicking.
+	///
+	/// This is the fallible counterpart of [`Dex::current_checksum`], suitable for untrusted input.
+	///
+	/// # Returns
+	/// A 4-byte array representing the checksum stored in the DEX file header.
+	///
+	/// # Errors
+	/// Returns [`DexError::TooShort`] if the DEX file is shorter than the 0x70-byte header.
+	pub fn try_current_checksum(&self) -> Result<[u8; 4], DexError> {
+		self.validate_minimum_length()?;
+		Ok(self.current_checksum())
+	}
+
+	/// Calculates the expected checksum for the DEX file.
+	///
+	/// This method computes the Adler-32 checksum for the data part of the DEX file
+	/// starting from byte 12 to the end of the file. The Adler-32 checksum is used
+	/// for error-checking of the data. It should match the current checksum in the
+	/// file header for the file to be considered valid.
+	///
+	/// The bytes are laid out according to [`Dex::endianness`], so they can be written
+	/// back into the header as-is.
+	///
+	/// A file of exactly 12 bytes has no data to checksum, so its expected checksum is the
+	/// Adler-32 of empty input, `0x00000001`. Such a file is not a meaningful DEX file; use
+	/// [`Dex::try_expect_checksum`] to reject anything shorter than the header instead.
+	///
+	/// # Returns
+	/// A 4-byte array representing the expected checksum for the DEX file.
+	///
+	/// # Panics
+	/// Panics if the DEX file is shorter than 12 bytes. Use [`Dex::try_expect_checksum`] for
+	/// untrusted input.
+	pub fn expect_checksum(&self) -> [u8; 4] {
+		checksum_of(&self.bytes)
+	}
+
+	/// Calculates the expected checksum like [`Dex::expect_checksum`], without panicking.
+	///
+	/// Computing the Adler-32 itself cannot fail, so a file that is too short is the only error.
+	///
+	/// # Errors
+	/// Returns [`DexError::TooShort`] if the DEX file is shorter than the 0x70-byte header,
+	/// including files truncated to their checksum field.
+	pub fn try_expect_checksum(&self) -> Result<[u8; 4], DexError> {
+		self.validate_minimum_length()?;
+		Ok(checksum_of(&self.bytes))
+	}
+
+	/// Returns the current checksum from the DEX file's header as an integer.
+	///
+	/// # Returns
+	/// The checksum stored in the DEX file header, decoded according to [`Dex::endianness`].
+	///
+	/// # Panics
+	/// Panics if the DEX file is shorter than 12 bytes.
+	pub fn current_checksum_u32(&self) -> u32 {
+		self
+			.read_u32(8)
+			.expect("DEX file is too short to hold a checksum!")
+	}
+
+	/// Returns the expected checksum for the DEX file as an integer.
+	///
+	/// # Returns
+	/// The Adler-32 checksum calculated over the data part of the DEX file.
+	pub fn expect_checksum_u32(&self) -> u32 {
+		self.endianness().decode_u32(self.expect_checksum())
+	}
+
+	/// Checks if the current checksum matches the expected checksum.
+	///
+	/// This method compares the current checksum from the file's header
+	/// with the expected checksum calculated over the data part of the file.
+	///
+	/// # Returns
+	/// - `true` if the checksums match.
+	/// - `false` otherwise.
+	pub fn check_checksum(&self) -> bool {
+		self.current_checksum() == self.expect_checksum()
+	}
+
+	/// Corrects the checksum in the DEX file header if it does not match the expected checksum.
+	///
+	/// This method calculates the expected checksum using the `expect_checksum` method
+	/// and updates the bytes in the DEX file header if the current checksum is incorrect.
+	/// After calling this method, the checksum in the file header should match the
+	/// expected checksum for the data part of the DEX file.
+	///
+	/// # Returns
+	/// - `true` if the checksum was wrong and a correction was applied.
+	/// - `false` if the checksum was already correct and the bytes were left untouched.
+	pub fn correct_checksum(&mut self) -> bool {
+		let expect = self.expect_checksum();
+		if self.current_checksum() != expect {
+			self.bytes[8..12].copy_from_slice(&expect);
+			true
+		} else {
+			false
+		}
+	}
+
+	/// Calculates the Adler-32 of an arbitrary range of the file, such as a single section.
+	///
+	/// Comparing the checksums of the same section of two builds, with offsets taken from
+	/// [`Dex::map_list`] or [`Dex::header`], pinpoints which section differs.
+	///
+	/// # Returns
+	/// The Adler-32 of `bytes[range]`, as an integer.
+	///
+	/// # Errors
+	/// Returns [`DexError::OffsetOutOfRange`] if `range` does not lie within the file, or if it
+	/// ends before it starts.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use dex_checksum_tools::Dex;
+	///
+	/// let dex = Dex::empty(35);
+	/// let mut patched = dex.clone();
+	/// patched.patch(0x80, &[0xff]).unwrap();
+	///
+	/// let header = dex.header().unwrap();
+	/// let start = header.data_off() as usize;
+	/// let data = start..start + header.data_size() as usize;
+	/// let rest_of_header = 32..start;
+	/// let checksum = |dex: &Dex, range| dex.checksum_of_range(range).unwrap();
+	/// assert_ne!(checksum(&dex, data.clone()), checksum(&patched, data));
+	/// assert_eq!(checksum(&dex, rest_of_header.clone()), checksum(&patched, rest_of_header));
+	/// assert!(dex.checksum_of_range(start..dex.len() + 1).is_err());
+	/// ```
+	pub fn checksum_of_range(&self, range: Range<usize>) -> Result<u32, DexError> {
+		if range.end < range.start {
+			return Err(DexError::OffsetOutOfRange {
+				start: range.start,
+				end: range.end,
+				len: self.bytes.len(),
+			});
+		}
+		let bytes = slice_at(&self.bytes, range.start, range.len())?;
+		Ok(adler::adler32(bytes))
+	}
+
+	/// Calculates the expected checksum for a variant layout, see [`ChecksumLayout`].
+	///
+	/// # Returns
+	/// The Adler-32 of `layout.body`, laid out according to [`Dex::endianness`].
+	///
+	/// # Errors
+	/// Returns [`DexError::OffsetOutOfRange`] if `layout.field` does not lie within the file,
+	/// or `layout.body` starts past its end.
+	///
+	/// # Panics
+	/// Panics if `layout.field` is not 4 bytes long.
+	pub fn expect_checksum_with_layout(&self, layout: &ChecksumLayout) -> Result<[u8; 4], DexError> {
+		assert_eq!(layout.field.len(), 4, "A checksum field is 4 bytes long!");
+		slice_at(&self.bytes, layout.field.start, 4)?;
+		let start = layout.body.start;
+		let end = layout.body.end.min(self.bytes.len());
+		let body = slice_at(&self.bytes, start, end.saturating_sub(start))?;
+		Ok(self.endianness().encode_u32(adler::adler32(body)))
+	}
+
+	/// Checks the checksum of a variant layout, like [`Dex::check_checksum`] does for
+	/// [`DEX_LAYOUT`].
+	///
+	/// # Errors
+	/// See [`Dex::expect_checksum_with_layout`].
+	pub fn check_checksum_with_layout(&self, layout: &ChecksumLayout) -> Result<bool, DexError> {
+		let expect = self.expect_checksum_with_layout(layout)?;
+		Ok(self.bytes[layout.field.clone()] == expect)
+	}
+
+	/// Corrects the checksum of a variant layout, like [`Dex::correct_checksum`] does for
+	/// [`DEX_LAYOUT`].
+	///
+	/// # Returns
+	/// - `Ok(true)` if the checksum was wrong and a correction was applied.
+	/// - `Ok(false)` if the checksum was already correct and the bytes were left untouched.
+	///
+	/// # Errors
+	/// See [`Dex::expect_checksum_with_layout`].
+	pub fn correct_checksum_with_layout(
+		&mut self,
+		layout: &ChecksumLayout,
+	) -> Result<bool, DexError> {
+		let expect = self.expect_checksum_with_layout(layout)?;
+		let field = &mut self.bytes[layout.field.clone()];
+		if *field != expect {
+			field.copy_from_slice(&expect);
+			Ok(true)
+		} else {
+			Ok(false)
+		}
+	}
+
+	/// Corrects the checksum like [`Dex::correct_checksum`], reporting the old and new values.
+	///
+	/// # Returns
+	/// - `Some((old, new))` if the checksum was corrected, with both values decoded as in [`Dex::current_checksum_u32`].
+	/// - `None` if the checksum was already correct.
+	pub fn correct_checksum_detailed(&mut self) -> Option<(u32, u32)> {
+		let old = self.current_checksum_u32();
+		if self.correct_checksum() {
+			Some((old, self.current_checksum_u32()))
+		} else {
+			None
+		}
+	}
+
+	/// Overwrites the bytes at `offset` with `data`, checking that they lie within the file.
+	///
+	/// The checksum and signature are not updated; call [`Dex::correct_all`] once all patches
+	/// have been applied, or use [`Dex::recompute_checksum_after_edit`] for a single small edit.
+	///
+	/// # Arguments
+	///
+	/// * `offset` - The offset of the first byte to overwrite.
+	/// * `data` - The bytes to store at `offset`.
+	///
+	/// # Errors
+	/// Returns [`DexError::OffsetOutOfRange`] if `offset..offset + data.len()` does not lie within the file.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use dex_checksum_tools::Dex;
+	///
+	/// let mut dex = Dex::empty(35);
+	/// dex.patch(0x2c, &[0; 8]).unwrap();
+	/// assert!(dex.patch(0x8a, &[0; 4]).is_err());
+	/// dex.correct_all();
+	/// ```
+	pub fn patch(&mut self, offset: usize, data: &[u8]) -> Result<(), DexError> {
+		slice_at(&self.bytes, offset, data.len())?;
+		self.bytes[offset..offset + data.len()].copy_from_slice(data);
+		Ok(())
+	}
+
+	/// Applies an in-place edit and updates the checksum incrementally, without rescanning the file.
+	///
+	/// This method replaces the bytes at `offset` (which must equal `old`) with `new`, and uses
+	/// the rolling property of Adler-32 to update the checksum stored in the header in
+	/// `O(new.len())` time. It assumes the stored checksum was correct before the edit; if it
+	/// was not, call [`Dex::correct_checksum`] instead.
+	///
+	/// Note that the edit invalidates the signature, which still has to be corrected separately.
+	///
+	/// # Arguments
+	///
+	/// * `offset` - The offset of the edit, which must lie within the checksummed data (12 onwards).
+	/// * `old` - The bytes currently stored at `offset`.
+	/// * `new` - The bytes to store at `offset`, of the same length as `old`.
+	///
+	/// # Errors
+	/// - [`DexError::OffsetOutOfRange`] if the edit does not lie within the checksummed data.
+	/// - [`DexError::EditMismatch`] if `old` and `new` differ in length, or `old` does not match the current bytes.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use dex_checksum_tools::Dex;
+	///
+	/// let mut dex = Dex::empty(35);
+	/// let old = dex.as_bytes()[0x70..0x74].to_vec();
+	/// dex.recompute_checksum_after_edit(0x70, &old, &[3, 0, 0, 0]).unwrap();
+	/// assert!(dex.check_checksum());
+	/// assert!(dex.recompute_checksum_after_edit(8, &[0; 4], &[1; 4]).is_err());
+	/// ```
+	pub fn recompute_checksum_after_edit(
+		&mut self,
+		offset: usize,
+		old: &[u8],
+		new: &[u8],
+	) -> Result<(), DexError> {
+		let end = offset.saturating_add(old.len());
+		if offset < 12 || end > self.bytes.len() {
+			return Err(DexError::OffsetOutOfRange {
+				start: offset,
+				end,
+				len: self.bytes.len(),
+			});
+		}
+		if old.len() != new.len() || &self.bytes[offset..end] != old {
+			return Err(DexError::EditMismatch { offset });
+		}
+
+		const MOD: u64 = 65521;
+		let checksum = self.current_checksum_u32();
+		let mut a = (checksum & 0xFFFF) as u64;
+		let mut b = (checksum >> 16) as u64;
+		let n = (self.bytes.len() - 12) as u64;
+		for (i, (&o, &w)) in old.iter().zip(new).enumerate() {
+			// Each data byte d at index k contributes d to A and (n - k) * d to B.
+			let weight = (n - (offset - 12 + i) as u64) % MOD;
+			let delta = (w as u64 + MOD - o as u64) % MOD;
+			a = (a + delta) % MOD;
+			b = (b + weight * delta) % MOD;
+		}
+		self.bytes[offset..end].copy_from_slice(new);
+		self.write_u32(8, ((b << 16) | a) as u32);
+		Ok(())
+	}
+
+	/// Extracts the current SHA-1 signature from the DEX file's header.
+	///
+	/// This method extracts the signature bytes that are stored at offset 12 through 31 in the DEX file header
+	/// and converts them into a 20-byte array. The signature is a SHA-1 hash of the rest of the file and is
+	/// used by the Android verifier to uniquely identify the file.
+	///
+	/// # Returns
+	/// A 20-byte array representing the signature stored in the DEX file header.
+	///
+	/// # Panics
+	/// Panics if the slice of bytes cannot be converted into an array, which indicates an issue with the DEX file format.
+	pub fn current_signature(&self) -> [u8; 20] {
+		self.bytes[12..32]
+			.try_into()
+			.expect("Could not convert slice to array!")
+	}
+
+	/// Extracts the current SHA-1 signature from the DEX file's header without panicking.
+	///
+	/// # Returns
+	/// A 20-byte array representing the signature stored in the DEX file header.
+	///
+	/// # Errors
+	/// Returns [`DexError::TooShort`] if the DEX file is shorter than the 0x70-byte header.
+	pub fn try_current_signature(&self) -> Result<[u8; 20], DexError> {
+		self.validate_minimum_length()?;
+		Ok(self.current_signature())
+	}
+
+	/// Returns the current signature as a 40-character lowercase hex string, the way most other
+	/// tools print a SHA-1.
+	///
+	/// # Panics
+	/// Panics if the DEX file is shorter than 32 bytes.
+	pub fn signature_hex(&self) -> String {
+		hex::encode(&self.current_signature())
+	}
+
+	/// Checks if the current signature matches a SHA-1 given as a hex string.
+	///
+	/// The string may use either case, start with `0x` and contain whitespace, such as a
+	/// trailing newline or spaces between groups of digits.
+	///
+	/// # Returns
+	/// - `true` if `expected` is 40 hex digits equal to the current signature.
+	/// - `false` otherwise, including if `expected` is not a valid hex string.
+	///
+	/// # Panics
+	/// Panics if the DEX file is shorter than 32 bytes.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use dex_checksum_tools::Dex;
+	///
+	/// let dex = Dex::empty(35);
+	/// let expected = format!("0x{}\n", dex.signature_hex().to_uppercase());
+	/// assert!(dex.signature_matches_hex(&expected));
+	/// assert!(!dex.signature_matches_hex("0x1234"));
+	/// ```
+	pub fn signature_matches_hex(&self, expected: &str) -> bool {
+		let digits: String = expected.split_whitespace().collect();
+		let digits = digits
+			.strip_prefix("0x")
+			.or_else(|| digits.strip_prefix("0X"))
+			.unwrap_or(&digits);
+		hex::decode(digits).is_some_and(|bytes| bytes == self.current_signature())
+	}
+
+	/// Computes the SHA-256 of the whole file, header included, as a content hash.
+	///
+	/// This is not part of the DEX format and is unrelated to [`Dex::current_signature`]: it is
+	/// meant for caching and deduplicating DEX files by their exact bytes. Since it covers the
+	/// checksum and signature too, two files with the same body but different digests hash
+	/// differently.
+	///
+	/// Only available with the `sha2` feature.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use dex_checksum_tools::Dex;
+	///
+	/// let mut dex = Dex::empty(35);
+	/// let hash = dex.content_hash_sha256();
+	/// assert_eq!(hash, Dex::empty(35).content_hash_sha256());
+	///
+	/// // Only the checksum changes, but the content hash covers it.
+	/// dex.patch(8, &[0; 4]).unwrap();
+	/// assert_ne!(hash, dex.content_hash_sha256());
+	/// ```
+	#[cfg(feature = "sha2")]
+	pub fn content_hash_sha256(&self) -> [u8; 32] {
+		sha256::sha256(&self.bytes)
+	}
+
+	/// Calculates the expected SHA-1 signature for the DEX file.
+	///
+	/// This method computes the SHA-1 hash for the data part of the DEX file
+	/// starting from byte 32 to the end of the file. It should match the current
+	/// signature in the file header for the file to be considered valid.
+	///
+	/// # Returns
+	/// A 20-byte array representing the expected signature for the DEX file.
+	pub fn expect_signature(&self) -> [u8; 20] {
+		sha1::sha1(&self.bytes[self.signature_region()])
+	}
+
+	/// Calculates the expected SHA-1 signature for the DEX file without panicking.
+	///
+	/// # Returns
+	/// A 20-byte array representing the expected signature for the DEX file.
+	///
+	/// # Errors
+	/// Returns [`DexError::TooShort`] if the DEX file is shorter than the 0x70-byte header.
+	pub fn try_expect_signature(&self) -> Result<[u8; 20], DexError> {
+		self.validate_minimum_length()?;
+		Ok(self.expect_signature())
+	}
+
+	/// Checks if the current signature matches the expected signature.
+	///
+	/// # Returns
+	/// - `true` if the signatures match.
+	/// - `false` otherwise.
+	pub fn check_signature(&self) -> bool {
+		self.current_signature() == self.expect_signature()
+	}
+
+	/// Corrects the signature in the DEX file header if it does not match the expected signature.
+	///
+	/// Note that the checksum covers the signature bytes, so correcting the signature invalidates
+	/// the checksum. Always call this method *before* [`Dex::correct_checksum`], or use
+	/// [`Dex::correct_all`] which does both in the right order.
+	///
+	/// # Returns
+	/// - `true` if the signature was wrong and a correction was applied.
+	/// - `false` if the signature was already correct and the bytes were left untouched.
+	pub fn correct_signature(&mut self) -> bool {
+		let expect = self.expect_signature();
+		if self.current_signature() != expect {
+			self.bytes[12..32].copy_from_slice(&expect);
+			true
+		} else {
+			false
+		}
+	}
+
+	/// Corrects both the signature and the checksum in the DEX file header.
+	///
+	/// This is what re-signing after an edit needs. The signature is written first, and the
+	/// checksum, which covers the signature bytes, is computed for the corrected signature.
+	///
+	/// The bytes are walked once, not once per digest: see [`Dex::compute_digests`]. The
+	/// checksum is combined from the Adler-32 of the new signature and of the rest of the file,
+	/// which the SHA-1 pass already read.
+	///
+	/// # Returns
+	/// - `true` if either the signature or the checksum was corrected.
+	/// - `false` if both were already correct and the bytes were left untouched.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use dex_checksum_tools::Dex;
+	///
+	/// let mut dex = Dex::empty(35);
+	/// dex.patch(0x6c, &[0xff]).unwrap();
+	/// assert!(dex.correct_all());
+	/// assert!(dex.check_signature() && dex.check_checksum());
+	/// assert!(!dex.correct_all());
+	/// ```
+	pub fn correct_all(&mut self) -> bool {
+		let digests = self.compute_digests();
+		let signature = self.current_signature() != digests.signature();
+		if signature {
+			self.bytes[12..32].copy_from_slice(&digests.signature());
+		}
+		let checksum = self.current_checksum() != digests.corrected_checksum();
+		if checksum {
+			self.bytes[8..12].copy_from_slice(&digests.corrected_checksum());
+		}
+		signature || checksum
+	}
+
+	/// Zeroes the checksum and the signature, `bytes[8..32]`, to canonicalize the file.
+	///
+	/// Reproducible-build comparisons hash canonicalized files, so that files whose digests were
+	/// recomputed by different tools compare equal. The result is stable for comparison but is
+	/// not a loadable DEX file; call [`Dex::correct_all`] to sign it again.
+	///
+	/// # Panics
+	/// Panics if the DEX file is shorter than 32 bytes.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use dex_checksum_tools::Dex;
+	///
+	/// let mut dex = Dex::empty(35);
+	/// dex.canonicalize();
+	/// assert!(dex.as_bytes()[8..32].iter().all(|&b| b == 0));
+	/// assert!(!dex.check_checksum() && !dex.check_signature());
+	///
+	/// assert!(dex.correct_all());
+	/// assert_eq!(dex, Dex::empty(35));
+	/// ```
+	pub fn canonicalize(&mut self) {
+		self.bytes[8..32].fill(0);
+	}
+
+	/// Corrects the `file_size` header field to the actual length of the DEX file.
+	///
+	/// Appending or truncating bytes leaves the field stale, and the Android verifier rejects
+	/// the file even with a correct checksum and signature. The field is covered by both, so
+	/// correct it first, or use [`Dex::correct_all_with_file_size`].
+	///
+	/// # Returns
+	/// - `true` if the field was wrong and a correction was applied.
+	/// - `false` if it was already correct.
+	///
+	/// # Panics
+	/// Panics if the DEX file is shorter than 36 bytes, or longer than `u32::MAX` bytes.
+	pub fn correct_file_size(&mut self) -> bool {
+		let len = u32::try_from(self.bytes.len()).expect("DEX file is too large!");
+		if self.read_u32(32) != Some(len) {
+			self.write_u32(32, len);
+			true
+		} else {
+			false
+		}
+	}
+
+	/// Corrects the `file_size` header field, then the signature and the checksum.
+	///
+	/// This is what a size-changing edit needs for the file to pass the verifier again.
+	///
+	/// # Returns
+	/// - `true` if any of the three was corrected.
+	/// - `false` otherwise.
+	///
+	/// # Panics
+	/// Panics if the DEX file is shorter than 36 bytes, or longer than `u32::MAX` bytes.
+	pub fn correct_all_with_file_size(&mut self) -> bool {
+		let file_size = self.correct_file_size();
+		let all = self.correct_all();
+		file_size || all
+	}
+
+	/// Computes the expected checksum and signature together, reading the file only once.
+	///
+	/// Calling [`Dex::expect_checksum`] and [`Dex::expect_signature`] separately reads most
+	/// of the file twice; this feeds each chunk into both hashers instead.
+	///
+	/// # Returns
+	/// The [`Digests`] of the DEX file.
+	///
+	/// # Panics
+	/// Panics if the DEX file is shorter than 32 bytes.
+	pub fn compute_digests(&self) -> Digests {
+		Digests::compute(&self.bytes, self.endianness())
+	}
+
+	/// Corrects the checksum like [`Dex::correct_checksum`], refusing inputs it would corrupt.
+	///
+	/// # Returns
+	/// - `Ok(true)` if the checksum was wrong and a correction was applied.
+	/// - `Ok(false)` if it was already correct.
+	///
+	/// # Errors
+	/// - Returns [`DexError::NotADex`] if the file is a ZIP archive, such as an APK.
+	/// - Returns [`DexError::UnsupportedFormat`] if the file is a CompactDex file or a DEX container.
+	/// - Returns [`DexError::TooShort`] if the DEX file is shorter than the 0x70-byte header.
+	/// - Returns [`DexError::BadMagic`] if the file does not start with a `dex\n???\0` magic.
+	pub fn try_correct_checksum(&mut self) -> Result<bool, DexError> {
+		self.ensure_supported_format()?;
+		Ok(self.correct_checksum())
+	}
+
+	/// Corrects the signature like [`Dex::correct_signature`], refusing inputs it would corrupt.
+	///
+	/// # Returns
+	/// - `Ok(true)` if the signature was wrong and a correction was applied.
+	/// - `Ok(false)` if it was already correct.
+	///
+	/// # Errors
+	/// - Returns [`DexError::NotADex`] if the file is a ZIP archive, such as an APK.
+	/// - Returns [`DexError::UnsupportedFormat`] if the file is a CompactDex file or a DEX container.
+	/// - Returns [`DexError::TooShort`] if the DEX file is shorter than the 0x70-byte header.
+	/// - Returns [`DexError::BadMagic`] if the file does not start with a `dex\n???\0` magic.
+	pub fn try_correct_signature(&mut self) -> Result<bool, DexError> {
+		self.ensure_supported_format()?;
+		Ok(self.correct_signature())
+	}
+
+	/// Corrects the signature and checksum like [`Dex::correct_all`], refusing inputs it would corrupt.
+	///
+	/// # Returns
+	/// - `Ok(true)` if either the signature or the checksum was corrected.
+	/// - `Ok(false)` otherwise.
+	///
+	/// # Errors
+	/// - Returns [`DexError::NotADex`] if the file is a ZIP archive, such as an APK.
+	/// - Returns [`DexError::UnsupportedFormat`] if the file is a CompactDex file or a DEX container.
+	/// - Returns [`DexError::TooShort`] if the DEX file is shorter than the 0x70-byte header.
+	/// - Returns [`DexError::BadMagic`] if the file does not start with a `dex\n???\0` magic.
+	pub fn try_correct_all(&mut self) -> Result<bool, DexError> {
+		self.ensure_supported_format()?;
+		Ok(self.correct_all())
+	}
+
+	/// Trims trailing bytes beyond the size declared by the `file_size` header field.
+	///
+	/// Some faulty packers append garbage to DEX files, which the checksum then covers but a
+	/// verifier does not expect. Call [`Dex::correct_all`] afterwards to sign the trimmed file.
+	/// A file shorter than its declared size is left untouched, since it cannot be repaired this
+	/// way.
+	///
+	/// # Returns
+	/// - `Ok(true)` if trailing bytes were trimmed.
+	/// - `Ok(false)` if the file is not longer than its declared size.
+	///
+	/// # Errors
+	/// - Returns [`DexError::TooShort`] if the DEX file is shorter than the 0x70-byte header.
+	/// - Returns [`DexError::OffsetOutOfRange`] if the declared size is smaller than the header,
+	///   since trimming to it would cut into the header.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::io::Write;
+	///
+	/// use dex_checksum_tools::Dex;
+	///
+	/// let mut dex = Dex::empty(35);
+	/// dex.write_all(b"trailing garbage").unwrap();
+	/// assert!(dex.truncate_to_declared_size().unwrap());
+	/// dex.correct_all();
+	/// assert_eq!(dex, Dex::empty(35));
+	/// ```
+	pub fn truncate_to_declared_size(&mut self) -> Result<bool, DexError> {
+		self.validate_minimum_length()?;
+		let declared = self.read_u32(32).expect("The header holds file_size") as usize;
+		let len = self.bytes.len();
+		if declared >= len {
+			return Ok(false);
+		}
+		if declared < header::HEADER_SIZE {
+			return Err(DexError::OffsetOutOfRange {
+				start: declared,
+				end: len,
+				len,
+			});
+		}
+		self.bytes.to_mut().truncate(declared);
+		Ok(true)
+	}
+
+	/// Checks if the `file_size` header field matches the actual length of the DEX file.
+	///
+	/// A truncated or padded DEX file will never pass the Android verifier, even with a
+	/// correct checksum and signature.
+	///
+	/// # Returns
+	/// - `true` if the declared size equals the number of bytes.
+	/// - `false` otherwise, including when the file is too short to contain the field.
+	pub fn check_file_size(&self) -> bool {
+		self
+			.read_u32(32)
+			.is_some_and(|file_size| file_size as usize == self.bytes.len())
+	}
+
+	/// Reads the `header_size` header field at offset 36, decoded according to [`Dex::endianness`].
+	///
+	/// # Returns
+	/// - `Some(header_size)` if the file is long enough to contain the field.
+	/// - `None` otherwise.
+	pub fn header_size(&self) -> Option<u32> {
+		self.read_u32(36)
+	}
+
+	/// Checks if the `header_size` header field is the 0x70 bytes of a standard DEX header.
+	///
+	/// A hand-edited header with a broken size field makes the file uninstallable, even with
+	/// a correct checksum and signature.
+	///
+	/// # Returns
+	/// - `true` if the declared header size is 0x70.
+	/// - `false` otherwise, including when the file is too short to contain the field.
+	pub fn check_header_size(&self) -> bool {
+		self.header_size() == Some(header::HEADER_SIZE as u32)
+	}
+
+	/// Writes the DEX file's bytes to the specified path.
+	///
+	/// This function creates a new file at the given `path` and writes the
+	/// bytes representing the DEX file to it. If the file already exists,
+	/// it will be truncated before writing.
+	///
+	/// # Arguments
+	///
+	/// * `path` - The path where the file will be written.
+	///
+	/// # Returns
+	///
+	/// An `io::Result<()>` which is `Ok` if the file was written successfully,
+	/// or an `Err` with more information if the file could not be written.
+	///
+	/// Only available with the `std-fs` feature, which is enabled by default.
+	#[cfg(feature = "std-fs")]
+	pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+		self.write_to(&mut File::create(path)?)
+	}
+
+	/// Writes the DEX file's bytes to the specified path, unless the file there already holds
+	/// exactly these bytes.
+	///
+	/// Skipping the write leaves the modification time of an unchanged file alone, so that
+	/// incremental builds do not consider it stale.
+	///
+	/// # Arguments
+	///
+	/// * `path` - The path where the file will be written.
+	///
+	/// # Returns
+	///
+	/// An `io::Result<bool>` which is `Ok(true)` if the file was written, `Ok(false)` if it
+	/// already held these bytes, or an `Err` if it could not be read or written.
+	///
+	/// Only available with the `std-fs` feature, which is enabled by default.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use dex_checksum_tools::Dex;
+	///
+	/// if let Ok(mut dex) = Dex::open("/path/to/input.dex") {
+	///     dex.correct_all();
+	///     match dex.save("/path/to/input.dex") {
+	///         Ok(true) => println!("corrected"),
+	///         Ok(false) => println!("already correct"),
+	///         Err(e) => println!("Failed to save: {}", e),
+	///     }
+	/// }
+	/// ```
+	#[cfg(feature = "std-fs")]
+	pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<bool> {
+		let path = path.as_ref();
+		match std::fs::metadata(path) {
+			// Only read the file back when a different length does not already tell them apart.
+			Ok(metadata) if metadata.len() == self.bytes.len() as u64 => {
+				if std::fs::read(path)? == *self.bytes {
+					return Ok(false);
+				}
+			}
+			Ok(_) => {}
+			Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+			Err(e) => return Err(e),
+		}
+		self.write_to_file(path)?;
+		Ok(true)
+	}
+
+	/// Writes the DEX file's bytes to the given writer.
+	///
+	/// # Arguments
+	///
+	/// * `writer` - Any destination implementing `Write`, such as a `File`, a `Vec<u8>` or stdout.
+	///
+	/// # Returns
+	///
+	/// An `io::Result<()>` which is `Ok` if all bytes were written successfully,
+	/// or an `Err` with more information if writing failed.
+	pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+		writer.write_all(&self.bytes)
+	}
+
+	/// Writes the DEX file's bytes to the given writer as a gzip file.
+	///
+	/// Only available with the `gzip` feature.
+	///
+	/// # Arguments
+	///
+	/// * `writer` - Any destination implementing `Write`.
+	///
+	/// # Returns
+	///
+	/// An `io::Result<()>` which is `Ok` if all bytes were written successfully,
+	/// or an `Err` with more information if writing failed.
+	#[cfg(feature = "gzip")]
+	pub fn write_gzip_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+		writer.write_all(&gzip::compress(&self.bytes))
+	}
+
+	/// Prints the DEX file's bytes as an `xxd` hex dump, 16 bytes per line, which
+	/// [`Dex::from_hexdump`] reads back.
+	///
+	/// # Returns
+	/// The hex dump, with one offset, hex and ASCII column line per 16 bytes.
+	pub fn to_hexdump(&self) -> String {
+		hexdump::format(&self.bytes)
+	}
+
+	/// Writes the DEX file's bytes to stdout and flushes it.
+	///
+	/// # Returns
+	///
+	/// An `io::Result<()>` which is `Ok` if all bytes were written successfully,
+	/// or an `Err` with more information if writing failed.
+	pub fn write_to_stdout(&self) -> io::Result<()> {
+		let stdout = io::stdout();
+		let mut handle = stdout.lock();
+		self.write_to(&mut handle)?;
+		handle.flush()
+	}
+}
+
+/// Checks whether two DEX files are identical apart from their checksum and signature fields.
+///
+/// Both digests are derived from the rest of the file, so this tells whether two builds
+/// produced the same DEX content, even if their digests were computed at different times or
+/// with different tools, or are simply stale.
+///
+/// # Returns
+/// - `true` if both files have the same length and the same bytes outside of `8..32`.
+/// - `false` otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use dex_checksum_tools::{bodies_equal, Dex};
+///
+/// let a = Dex::empty(35);
+/// let mut b = a.clone();
+/// b.patch(8, &[0; 24]).unwrap();
+/// assert!(bodies_equal(&a, &b));
+/// assert!(!bodies_equal(&a, &Dex::empty(39)));
+/// ```
+pub fn bodies_equal(a: &Dex, b: &Dex) -> bool {
+	fn outside_digests(bytes: &[u8]) -> (&[u8], &[u8]) {
+		(&bytes[..bytes.len().min(8)], bytes.get(32..).unwrap_or(&[]))
+	}
+	a.len() == b.len() && outside_digests(a.as_bytes()) == outside_digests(b.as_bytes())
+}
+
+/// Corrects the SHA-1 signature and then the checksum of a DEX file given as bytes, returning
+/// the corrected bytes.
+///
+/// This is [`Dex::try_correct_all`] for callers that only hold bytes and want bytes back, such
+/// as build tools that patch a DEX file and then fix it up before writing it out.
+///
+/// # Arguments
+///
+/// * `bytes` - The bytes of exactly one DEX file. They are corrected in place, without copying.
+///
+/// # Returns
+///
+/// The corrected bytes, which are `bytes` unchanged if both digests were already correct.
+///
+/// # Errors
+/// - Returns [`DexError::NotADex`] if `bytes` holds a ZIP archive, such as an APK.
+/// - Returns [`DexError::UnsupportedFormat`] if `bytes` holds a CompactDex file or a DEX container.
+/// - Returns [`DexError::TooShort`] if `bytes` is shorter than the 0x70-byte header.
+/// - Returns [`DexError::BadMagic`] if `bytes` does not start with a `dex\n???\0` magic.
+///
+/// # Examples
+///
+/// ```
+/// use dex_checksum_tools::{correct_dex_bytes, Dex};
+///
+/// let mut bytes = Dex::empty(35).into_bytes();
+/// bytes[8] ^= 0xff;
+/// let corrected = correct_dex_bytes(bytes).unwrap();
+/// assert_eq!(corrected, Dex::empty(35).into_bytes());
+/// ```
+pub fn correct_dex_bytes(bytes: Vec<u8>) -> Result<Vec<u8>, DexError> {
+	let mut dex = Dex::from_bytes(bytes);
+	dex.try_correct_all()?;
+	Ok(dex.into_bytes())
+}
+
+/// Rejects ZIP archives such as APKs, which are easily passed where a DEX file is expected.
+pub(crate) fn ensure_not_zip(bytes: &[u8]) -> Result<(), DexError> {
+	if zip::is_zip(bytes) {
+		Err(DexError::NotADex { looks_like: "zip" })
+	} else {
+		Ok(())
+	}
+}
+
+/// Checks if `bytes` starts with a `dex\n???\0` magic, whose version is three digits.
+pub(crate) fn is_dex_magic(bytes: &[u8]) -> bool {
+	match bytes.get(0..8) {
+		Some(magic) => {
+			&magic[0..4] == b"dex\n" && magic[4..7].iter().all(u8::is_ascii_digit) && magic[7] == 0
+		}
+		None => false,
+	}
+}
+
+/// Checks if `bytes` starts with a `cdex???\0` magic.
+pub(crate) fn is_compact_dex_magic(bytes: &[u8]) -> bool {
+	match bytes.get(0..8) {
+		Some(magic) => &magic[0..4] == b"cdex" && magic[7] == 0,
+		None => false,
+	}
+}
+
+/// Checks if `bytes` starts with a `dex\n???\0` magic of version 041 or later, the first
+/// version with DEX containers.
+pub(crate) fn is_container_dex_magic(bytes: &[u8]) -> bool {
+	match bytes.get(0..8) {
+		Some(magic) if &magic[0..4] == b"dex\n" && magic[7] == 0 => std::str::from_utf8(&magic[4..7])
+			.ok()
+			.and_then(|version| version.parse::<u32>().ok())
+			.is_some_and(|version| version >= 41),
+		_ => false,
+	}
+}
+
+/// Returns `len` bytes at `start`, or [`DexError::OffsetOutOfRange`] if they lie outside of `bytes`.
+pub(crate) fn slice_at(bytes: &[u8], start: usize, len: usize) -> Result<&[u8], DexError> {
+	let out_of_range = || DexError::OffsetOutOfRange {
+		start,
+		end: start.saturating_add(len),
+		len: bytes.len(),
+	};
+	let end = start.checked_add(len).ok_or_else(out_of_range)?;
+	bytes.get(start..end).ok_or_else(out_of_range)
+}
+
+impl Dex {
+	fn ensure_supported_format(&self) -> Result<(), DexError> {
+		ensure_not_zip(&self.bytes)?;
+		if self.is_compact_dex() || self.is_container_dex() {
+			return Err(DexError::UnsupportedFormat(self.magic()));
+		}
+		self.validate_minimum_length()?;
+		if !self.is_valid_magic() {
+			return Err(DexError::BadMagic(self.magic()));
+		}
+		Ok(())
+	}
+
+	/// Reads the 4-byte header field at `offset`, decoded according to [`Dex::endianness`].
+	///
+	/// Every integer header field is read through here, so that the byte order is decided in
+	/// one place.
+	///
+	/// # Returns
+	/// - `Some(value)` if the file is long enough to contain the field.
+	/// - `None` otherwise.
+	fn read_u32(&self, offset: usize) -> Option<u32> {
+		let field = self.bytes.get(offset..offset.checked_add(4)?)?;
+		Some(self.endianness().decode_u32(field.try_into().unwrap()))
+	}
+
+	/// Writes `value` to the 4-byte header field at `offset`, encoded according to
+	/// [`Dex::endianness`], the counterpart of [`Dex::read_u32`].
+	///
+	/// # Panics
+	/// Panics if the file is too short to contain the field.
+	fn write_u32(&mut self, offset: usize, value: u32) {
+		let encoded = self.endianness().encode_u32(value);
+		self.bytes[offset..offset + 4].copy_from_slice(&encoded);
+	}
+
+	fn ensure_len(&self, needed: usize) -> Result<(), DexError> {
+		if self.bytes.len() < needed {
+			Err(DexError::TooShort {
+				needed,
+				got: self.bytes.len(),
+			})
+		} else {
+			Ok(())
+		}
+	}
+}
+
+impl Display for Dex {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "Dex {{ bytes: {:?} }}", self.bytes)
+	}
+}
+
+/// Compares the bytes only, not where they were read from.
+impl PartialEq for Dex {
+	fn eq(&self, other: &Self) -> bool {
+		self.bytes == other.bytes
+	}
+}
+
+impl Eq for Dex {}
+
+impl Default for Dex {
+	/// Creates a `Dex` with no bytes, like [`Dex::new`].
+	fn default() -> Self {
+		Dex::new()
+	}
+}
+
+/// Appends to the end of the DEX file, for code generators that serialize the header and
+/// sections straight into a `Dex`.
+///
+/// Nothing is validated or corrected while writing: call [`Dex::correct_all_with_file_size`]
+/// once everything is written.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Write;
+///
+/// use dex_checksum_tools::Dex;
+///
+/// let mut dex = Dex::empty(35);
+/// dex.write_all(&[0; 16]).unwrap();
+/// assert!(!dex.check_file_size());
+/// dex.correct_all_with_file_size();
+/// assert!(dex.check_checksum() && dex.check_signature() && dex.check_file_size());
+/// ```
+impl Write for Dex {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.bytes.to_mut().extend_from_slice(buf);
+		Ok(buf.len())
+	}
+
+	fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+		self.bytes.to_mut().extend_from_slice(buf);
+		Ok(())
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+
+#[cfg(feature = "std-fs")]
+impl TryFrom<File> for Dex {
+	type Error = io::Error;
+
+	fn try_from(f: File) -> Result<Self, Self::Error> {
+		Dex::from_reader(f)
+	}
+}
+
+/// Opens the DEX file at the given path, like [`Dex::open`]. Strings are always paths, never
+/// contents; use `TryFrom<Vec<u8>>` or `TryFrom<&[u8]>` to build a `Dex` from its bytes.
+///
+/// Prefer [`Dex::open`], whose name leaves no doubt that it takes a path.
+#[cfg(feature = "std-fs")]
+impl TryFrom<String> for Dex {
+	type Error = io::Error;
+
+	fn try_from(path: String) -> Result<Self, Self::Error> {
+		Dex::open(path)
+	}
+}
+
+/// Opens the DEX file at the given path, like [`Dex::open`]. Strings are always paths, never
+/// contents; use `TryFrom<Vec<u8>>` or `TryFrom<&[u8]>` to build a `Dex` from its bytes.
+///
+/// Prefer [`Dex::open`], whose name leaves no doubt that it takes a path.
+#[cfg(feature = "std-fs")]
+impl TryFrom<&str> for Dex {
+	type Error = io::Error;
+
+	fn try_from(path: &str) -> Result<Self, Self::Error> {
+		Dex::open(path)
+	}
+}
+
+/// Builds a `Dex` from the contents of a DEX file. Byte types are always contents,
+/// never paths; use [`Dex::open`] to open a file.
+///
+/// The bytes are checked like [`Dex::try_from_bytes`] does, and must also hold a whole
+/// 0x70-byte header.
+///
+/// # Errors
+/// - [`DexError::NotADex`] if the bytes are a ZIP archive, such as an APK.
+/// - [`DexError::UnsupportedFormat`] if the magic is a CompactDex magic.
+/// - [`DexError::BadMagic`] if the magic is not a DEX magic.
+/// - [`DexError::TooShort`] if the bytes cannot hold a 0x70-byte header.
+impl TryFrom<Vec<u8>> for Dex {
+	type Error = DexError;
+
+	fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
+		let dex = Dex::try_from_bytes(bytes)?;
+		dex.validate_minimum_length()?;
+		Ok(dex)
+	}
+}
+
+/// Builds a `Dex` by copying the contents of a DEX file. Byte types are always contents,
+/// never paths; use [`Dex::open`] to open a file.
+///
+/// The bytes are checked like `TryFrom<Vec<u8>>` does.
+///
+/// # Errors
+/// - [`DexError::NotADex`] if the bytes are a ZIP archive, such as an APK.
+/// - [`DexError::UnsupportedFormat`] if the magic is a CompactDex magic.
+/// - [`DexError::BadMagic`] if the magic is not a DEX magic.
+/// - [`DexError::TooShort`] if the bytes cannot hold a 0x70-byte header.
+impl TryFrom<&[u8]> for Dex {
+	type Error = DexError;
+
+	fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+		Dex::try_from(bytes.to_vec())
 	}
 }
@@ -1,112 +1,1571 @@
+use dex_checksum_tools::apk::Apk;
+use dex_checksum_tools::batch;
+use dex_checksum_tools::batch::{BatchOptions, FileError, FileResult};
+use dex_checksum_tools::hex;
 use dex_checksum_tools::Dex;
-use std::io::{stdin, Read};
+use dex_checksum_tools::DexError;
+use dex_checksum_tools::DexHeader;
+use std::env;
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::io::{stdin, IsTerminal, Read};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use structopt::clap::ErrorKind;
 use structopt::StructOpt;
 
+/// Whether `--verbose` was given, so that every step of a correction is logged to stderr.
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+
+/// Whether `--quiet` was given, so that informational messages are not printed.
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Whether pass and fail results are colored, as decided by `--color`. Never set with `--json`.
+static COLOR: AtomicBool = AtomicBool::new(false);
+
+/// Prints an informational message, such as `done.`, to stdout unless `--quiet` was given.
+macro_rules! report {
+	($($arg:tt)*) => {
+		if !QUIET.load(Ordering::Relaxed) {
+			println!($($arg)*);
+		}
+	};
+}
+
+/// Logs a step of the tool's work to stderr when `--verbose` was given.
+macro_rules! verbose {
+	($($arg:tt)*) => {
+		if VERBOSE.load(Ordering::Relaxed) {
+			eprintln!($($arg)*);
+		}
+	};
+}
+
+/// The environment variable holding options added to every command, such as `--signature`.
+const DEFAULT_OPTIONS_VAR: &str = "DEX_CHECKSUM_TOOLS_OPTS";
+
 fn main() {
-	let args = Args::from_args();
+	let (args, defaults, ignored) = parse_args();
 	if args.debug {
 		dbg!(&args);
 	}
+	VERBOSE.store(args.verbose, Ordering::Relaxed);
+	if !defaults.is_empty() {
+		verbose!("added {} from {}", defaults.join(" "), DEFAULT_OPTIONS_VAR);
+	}
+	if !ignored.is_empty() {
+		verbose!("ignored {} from {}", ignored.join(" "), DEFAULT_OPTIONS_VAR);
+	}
+	QUIET.store(args.quiet, Ordering::Relaxed);
+	COLOR.store(!args.json && args.color.enabled(), Ordering::Relaxed);
+	let json = args.json;
+	let mkdirs = args.mkdirs;
+	// A pipe carries DEX bytes far more often than a path, so a path is only read from a terminal.
+	let stdin_bytes = args.stdin_bytes || (!args.stdin_path && !stdin().is_terminal());
 	match args.opt {
-		Opt::CurrentChecksum { input_dex_file } => match input_dex_file.as_deref() {
-			None | Some("-") => {
-				let mut path = String::new();
-				let stdin = stdin();
-				let mut handle = stdin.lock();
-				handle
-					.read_to_string(&mut path)
-					.expect("Failed to read from stdin!");
-				let checksum = Dex::try_from(path.trim())
-					.expect("Failed to read from stdin!")
-					.current_checksum();
-				println!("{:?}", checksum);
-			}
-			Some(path) => {
-				let checksum = Dex::try_from(path)
-					.expect("Failed to read from stdin!")
-					.current_checksum();
-				println!("{:?}", checksum);
+		Opt::CurrentChecksum {
+			input_dex_file,
+			format,
+		} => {
+			let (_, dex) = load_dex(input_dex_file, stdin_bytes);
+			if json {
+				println!(
+					"{}",
+					JsonObject::new().string("current", &format_u32(dex.current_checksum_u32()))
+				);
+			} else {
+				println!(
+					"{}",
+					format.render(dex.current_checksum(), dex.current_checksum_u32())
+				);
 			}
-		},
-		Opt::ExpectChecksum { input_dex_file } => match input_dex_file.as_deref() {
-			None | Some("-") => {
-				let mut path = String::new();
-				let stdin = stdin();
-				let mut handle = stdin.lock();
-				handle
-					.read_to_string(&mut path)
-					.expect("Failed to read from stdin!");
-				let checksum = Dex::try_from(path.trim())
-					.expect("Failed to read from stdin")
-					.expect_checksum();
-				println!("{:?}", checksum);
-			}
-			Some(path) => {
-				let checksum = Dex::try_from(path)
-					.expect("Failed to read from stdin!")
-					.expect_checksum();
-				println!("{:?}", checksum);
+		}
+		Opt::ExpectChecksum {
+			input_dex_file,
+			format,
+		} => {
+			let (_, dex) = load_dex(input_dex_file, stdin_bytes);
+			if json {
+				println!(
+					"{}",
+					JsonObject::new().string("expected", &format_u32(dex.expect_checksum_u32()))
+				);
+			} else {
+				println!(
+					"{}",
+					format.render(dex.expect_checksum(), dex.expect_checksum_u32())
+				);
 			}
-		},
+		}
+		Opt::Verify {
+			files_from,
+			tree,
+			signature,
+			junit,
+			..
+		} if files_from.is_some() || tree.is_some() => {
+			let mut files = Vec::new();
+			if let Some(list) = files_from {
+				files.extend(read_file_list(&list));
+			}
+			if let Some(root) = tree {
+				files.extend(find_dex_files(Path::new(&root)).into_iter().map(|path| {
+					let path = path.to_string_lossy().into_owned();
+					(path.clone(), path)
+				}));
+			}
+			verify_files(files, signature, json, junit.as_deref());
+		}
+		Opt::Verify {
+			input_dex_file,
+			signature,
+			junit,
+			..
+		} => {
+			if junit.is_some() {
+				fail("--junit reports on many files, pass them with --files-from or --tree".to_string());
+			}
+			let (_, dex) = load_dex(input_dex_file, stdin_bytes);
+			let checksum_valid = dex.check_checksum();
+			let signature_valid = !signature || dex.check_signature();
+			if json {
+				let mut object = checksum_json(&dex);
+				if signature {
+					object = signature_json(object, &dex);
+				}
+				println!(
+					"{}",
+					object
+						.bool("file_size_valid", dex.check_file_size())
+						.bool("header_size_valid", dex.check_header_size())
+				);
+			} else {
+				if checksum_valid {
+					println!("{}", paint("checksum ok.", Style::Pass));
+				} else {
+					let message = format!(
+						"checksum mismatch: current {:?}, expected {:?}.",
+						dex.current_checksum(),
+						dex.expect_checksum()
+					);
+					println!("{}", paint(&message, Style::Fail));
+				}
+				if signature {
+					if dex.check_signature() {
+						println!("{}", paint("signature ok.", Style::Pass));
+					} else {
+						let message = format!(
+							"signature mismatch: current {:?}, expected {:?}.",
+							dex.current_signature(),
+							dex.expect_signature()
+						);
+						println!("{}", paint(&message, Style::Fail));
+					}
+				}
+				if !dex.check_file_size() {
+					let message = format!(
+						"warning: file_size in the header does not match the actual size of {} bytes.",
+						dex.len()
+					);
+					println!("{}", paint(&message, Style::Warning));
+				}
+				if !dex.check_header_size() {
+					let message = "warning: header_size in the header is not 0x70.";
+					println!("{}", paint(message, Style::Warning));
+				}
+			}
+			if !checksum_valid || !signature_valid {
+				process::exit(1);
+			}
+		}
+		Opt::Diff { input_dex_file } => {
+			let (_, dex) = load_input(input_dex_file, stdin_bytes);
+			let checks = diagnose(&dex);
+			if json {
+				let rows: Vec<String> = checks
+					.iter()
+					.map(|check| {
+						JsonObject::new()
+							.string("check", check.name)
+							.bool("pass", check.pass)
+							.string("current", &check.current)
+							.string("expected", &check.expected)
+							.to_string()
+					})
+					.collect();
+				println!(
+					"{}",
+					JsonObject::new().raw("checks", format!("[{}]", rows.join(",")))
+				);
+			} else {
+				print_table(
+					["CHECK", "RESULT", "CURRENT", "EXPECTED"],
+					checks.iter().map(|check| {
+						[
+							check.name.to_string(),
+							match check.pass {
+								true => paint("pass", Style::Pass),
+								false => paint("FAIL", Style::Fail),
+							},
+							check.current.clone(),
+							check.expected.clone(),
+						]
+					}),
+				);
+			}
+			if checks.iter().any(|check| !check.pass) {
+				process::exit(1);
+			}
+		}
+		Opt::Header { input_dex_file } => {
+			let (_, dex) = load_dex(input_dex_file, stdin_bytes);
+			let header = dex
+				.header()
+				.unwrap_or_else(|e| fail(format!("Failed to parse header: {}", e)));
+			let fields = header_fields(&dex, &header);
+			if json {
+				let object =
+					fields
+						.into_iter()
+						.fold(JsonObject::new(), |object, (name, value)| match value {
+							HeaderValue::Text(text) => object.string(name, &text),
+							HeaderValue::Size(n) | HeaderValue::Offset(n) => object.raw(name, n.to_string()),
+						});
+				println!("{}", object);
+			} else {
+				print_table(
+					["FIELD", "VALUE"],
+					fields
+						.into_iter()
+						.map(|(name, value)| [name.to_string(), value.to_string()]),
+				);
+			}
+		}
 		Opt::CorrectChecksum {
 			input_dex_file,
 			output_dex_file,
+			dry_run,
+			force,
+			signature,
+			preserve,
+			backup,
+			numbered_backups,
 		} => {
-			let correct_checksum = |input_path: &str| {
-				let mut dex = Dex::try_from(input_path).expect("Failed to read from stdin!");
-				let out = match output_dex_file.as_deref() {
-					None => input_path,
-					Some(path) => path,
-				};
-				if dex.correct_checksum() || out != input_path {
+			let (input_path, mut dex) = load_dex(input_dex_file, stdin_bytes);
+			// As with the input, "-" means stdout rather than a file named "-".
+			let out = match output_dex_file {
+				Some(out) if out == "-" => None,
+				out => out.or(input_path.clone()),
+			};
+			let in_place = out.is_some() && out == input_path;
+			if out.is_none() && backup.is_some() && !dry_run {
+				fail("--backup needs an output file: stdout cannot be backed up".to_string());
+			}
+			let origin = input_path.as_deref().unwrap_or("stdin");
+			let current = dex.current_checksum_u32();
+			// The checksum covers the signature, so the signature must be corrected first.
+			if signature {
+				trace_signature(origin, &dex);
+			}
+			let signature_changed = signature
+				&& dex
+					.try_correct_signature()
+					.unwrap_or_else(|e| fail(format!("Failed to correct signature: {}", e)));
+			if signature_changed {
+				verbose!("{}: wrote signature", origin);
+			}
+			trace_checksum(origin, &dex);
+			let checksum_changed = dex
+				.try_correct_checksum()
+				.unwrap_or_else(|e| fail(format!("Failed to correct checksum: {}", e)));
+			if checksum_changed {
+				verbose!("{}: wrote checksum", origin);
+			}
+			let changed = signature_changed || checksum_changed;
+			let corrected = match (signature_changed, checksum_changed) {
+				(true, true) => "signature and checksum",
+				(true, false) => "signature",
+				_ => "checksum",
+			};
+			let written = match out.as_deref() {
+				_ if dry_run => false,
+				None => {
 					dex
-						.write_to_file(out)
-						.expect(format!("Failed to write to {}", out).as_str());
-					println!("done.")
-				} else {
-					println!("nothing to do.")
+						.write_to_stdout()
+						.unwrap_or_else(|e| fail(format!("Failed to write to stdout: {}", e)));
+					verbose!("{}: wrote {} bytes to stdout", origin, dex.len());
+					true
+				}
+				Some(out) if changed || force => {
+					// The file about to be overwritten is backed up, whether it is the input or not.
+					if let Some(suffix) = backup.as_ref().filter(|_| Path::new(out).exists()) {
+						let suffix = suffix.as_deref().unwrap_or(".bak");
+						let backup = batch::backup_file(Path::new(out), suffix, numbered_backups)
+							.unwrap_or_else(|e| fail(format!("Failed to back up {}: {}", out, e)));
+						verbose!("{}: backed up {} to {}", origin, out, backup.display());
+					}
+					let result = if in_place {
+						batch::replace_file(&dex, Path::new(out), preserve)
+					} else {
+						ensure_parent_dir(Path::new(out), mkdirs);
+						batch::write_file(&dex, out)
+					};
+					result.unwrap_or_else(|e| fail(format!("Failed to write to {}: {}", out, e)));
+					verbose!("{}: saved to {}", origin, out);
+					true
 				}
+				Some(_) => false,
 			};
-
-			match input_dex_file.as_deref() {
-				None | Some("-") => {
-					let mut path = String::new();
-					let stdin = stdin();
-					let mut handle = stdin.lock();
-					handle
-						.read_to_string(&mut path)
-						.expect("Failed to read from stdin!");
-					correct_checksum(path.trim());
+			let message = if json {
+				// Built after both corrections, so that `expected` is the checksum written.
+				let written_checksum = dex.current_checksum_u32();
+				JsonObject::new()
+					.string("current", &format_u32(current))
+					.string("expected", &format_u32(written_checksum))
+					.bool("valid", current == written_checksum)
+					.bool("signature_valid", dex.check_signature())
+					.bool("changed", changed)
+					.bool("checksum_changed", checksum_changed)
+					.bool("signature_changed", signature_changed)
+					.bool("dry_run", dry_run)
+					.to_string()
+			} else if dry_run && changed {
+				format!(
+					"would correct the {}: checksum current {}, corrected {}.",
+					corrected,
+					format_u32(current),
+					format_u32(dex.current_checksum_u32())
+				)
+			} else if written && changed {
+				format!("done, corrected the {}.", corrected)
+			} else if written {
+				"done.".to_string()
+			} else {
+				"nothing to do.".to_string()
+			};
+			// When the DEX bytes go to stdout, the report must not be mixed into them.
+			if out.is_none() {
+				if json || !QUIET.load(Ordering::Relaxed) {
+					eprintln!("{}", message);
 				}
-				Some(path) => {
-					correct_checksum(path.trim());
+			} else if json {
+				println!("{}", message);
+			} else {
+				report!("{}", message);
+			}
+		}
+		Opt::CorrectAll {
+			input_dex_files,
+			jobs,
+			signature,
+			progress,
+			preserve,
+			files_from,
+			strict,
+			out_dir,
+		} => {
+			let mut inputs: Vec<(String, String)> = input_dex_files
+				.into_iter()
+				.map(|path| (path.clone(), path))
+				.collect();
+			if let Some(list) = files_from {
+				inputs.extend(read_file_list(&list));
+			}
+			let paths: Vec<&str> = inputs.iter().map(|(path, _)| path.as_str()).collect();
+			let options = BatchOptions {
+				jobs,
+				signature,
+				preserve_mtime: preserve,
+				out_dir: out_dir.map(PathBuf::from),
+				..BatchOptions::default()
+			};
+			let mut progress = Progress::new(progress, inputs.len());
+			let results = batch::correct_files_with(&paths, &options, |path, result| {
+				progress.tick();
+				trace_file_result(path, result, false);
+			});
+			let mut summary = Summary::default();
+			for ((path, origin), result) in inputs.iter().zip(results) {
+				report_file_result(path, origin, &result, strict, json, false, &mut summary);
+			}
+			summary.finish(json, false);
+		}
+		Opt::CorrectTree {
+			root_dir,
+			dry_run,
+			signature,
+			progress,
+			preserve,
+			strict,
+			out_dir,
+		} => {
+			let root = Path::new(&root_dir);
+			let paths = find_dex_files(root);
+			let options = BatchOptions {
+				dry_run,
+				signature,
+				preserve_mtime: preserve,
+				out_dir: out_dir.map(PathBuf::from),
+				base_dir: Some(root.to_path_buf()),
+				..BatchOptions::default()
+			};
+			let mut progress = Progress::new(progress, paths.len());
+			let mut summary = Summary::default();
+			batch::correct_files_with(&paths, &options, |path, result| {
+				progress.tick();
+				trace_file_result(path, result, dry_run);
+				report_file_result(path, path, result, strict, json, dry_run, &mut summary);
+			});
+			summary.finish(json, dry_run);
+		}
+		Opt::CorrectApk {
+			input_apk_file,
+			output_apk_file,
+			store,
+		} => {
+			let mut apk = Apk::open(&input_apk_file)
+				.unwrap_or_else(|e| fail(format!("Failed to read {}: {}", input_apk_file, e)));
+			let results = if store {
+				apk.correct_all_stored()
+			} else {
+				apk.correct_all()
+			};
+			let mut summary = Summary::default();
+			for result in results {
+				let name = &result.name;
+				match &result.error {
+					Some(_) => summary.errors += 1,
+					None if result.changed => summary.corrected += 1,
+					None => summary.unchanged += 1,
+				}
+				if json {
+					let mut object = JsonObject::new()
+						.string("name", name)
+						.bool("changed", result.changed);
+					if let Some(checksum) = result.old_checksum {
+						object = object.string("old_checksum", &format_u32(checksum));
+					}
+					if let Some(checksum) = result.new_checksum {
+						object = object.string("new_checksum", &format_u32(checksum));
+					}
+					if let Some(e) = &result.error {
+						object = object.string("error", &e.to_string());
+					}
+					println!("{}", object);
+				} else if let Some(e) = &result.error {
+					eprintln!("{}: failed to correct: {}", name, e);
+				} else if result.changed {
+					report!("{}: done.", name);
+				} else {
+					report!("{}: nothing to do.", name);
 				}
 			}
+			// A partially corrected archive is not written, like a file that failed to correct.
+			if summary.errors == 0 {
+				ensure_parent_dir(Path::new(&output_apk_file), mkdirs);
+				apk
+					.write_to_file(&output_apk_file)
+					.unwrap_or_else(|e| fail(format!("Failed to write to {}: {}", output_apk_file, e)));
+			}
+			summary.finish(json, false);
+		}
+		Opt::VerifyApk { input_apk_file } => {
+			let apk = Apk::open(&input_apk_file)
+				.unwrap_or_else(|e| fail(format!("Failed to read {}: {}", input_apk_file, e)));
+			let mut all_valid = true;
+			for name in apk.dex_names() {
+				// An entry that cannot be read is reported as invalid, and the others still audited.
+				let read = match apk.dex(name) {
+					Ok(Some(dex)) => dex.validate_minimum_length().map(|()| dex),
+					Ok(None) => Err(DexError::Zip(format!(
+						"{} is missing from the archive",
+						name
+					))),
+					Err(e) => Err(e),
+				};
+				let dex = match read {
+					Ok(dex) => dex,
+					Err(e) => {
+						all_valid = false;
+						if json {
+							println!(
+								"{}",
+								JsonObject::new()
+									.string("name", name)
+									.string("error", &e.to_string())
+							);
+						} else {
+							println!("{}: {}.", name, e);
+						}
+						continue;
+					}
+				};
+				let checksum_valid = !dex.is_compact_dex() && dex.check_checksum();
+				let signature_valid = !dex.is_compact_dex() && dex.check_signature();
+				all_valid &= checksum_valid && signature_valid;
+				if json {
+					println!(
+						"{}",
+						JsonObject::new()
+							.string("name", name)
+							.bool("checksum_valid", checksum_valid)
+							.bool("signature_valid", signature_valid)
+					);
+				} else {
+					let status = |valid| match valid {
+						true => paint("ok", Style::Pass),
+						false => paint("mismatch", Style::Fail),
+					};
+					println!(
+						"{}: checksum {}, signature {}.",
+						name,
+						status(checksum_valid),
+						status(signature_valid)
+					);
+				}
+			}
+			if !all_valid {
+				process::exit(1);
+			}
 		}
 	}
 }
 
+/// Recursively collects the `*.dex` files under `root`, in sorted order.
+///
+/// Symbolic links are never followed, to avoid loops and escaping the tree.
+fn find_dex_files(root: &Path) -> Vec<PathBuf> {
+	let mut files = Vec::new();
+	let mut pending = vec![root.to_path_buf()];
+	while let Some(dir) = pending.pop() {
+		let entries = match fs::read_dir(&dir) {
+			Ok(entries) => entries,
+			Err(e) => {
+				eprintln!("{}: failed to read directory: {}", dir.display(), e);
+				continue;
+			}
+		};
+		for entry in entries.flatten() {
+			let path = entry.path();
+			match entry.file_type() {
+				Ok(file_type) if file_type.is_dir() => pending.push(path),
+				Ok(file_type)
+					if file_type.is_file() && path.extension().is_some_and(|ext| ext == "dex") =>
+				{
+					files.push(path)
+				}
+				_ => {}
+			}
+		}
+	}
+	files.sort();
+	files
+}
+
+/// The outcome counts of a batch run.
+#[derive(Default)]
+struct Summary {
+	corrected: usize,
+	unchanged: usize,
+	errors: usize,
+}
+
+impl Summary {
+	/// Prints the one-line summary, unless printing JSON, and exits non-zero if any file errored.
+	fn finish(&self, json: bool, dry_run: bool) {
+		if !json {
+			let verb = if dry_run {
+				"would be corrected"
+			} else {
+				"corrected"
+			};
+			let errors = if self.errors == 1 { "error" } else { "errors" };
+			report!(
+				"{} {}, {} unchanged, {} {}",
+				self.corrected,
+				verb,
+				self.unchanged,
+				self.errors,
+				errors
+			);
+		}
+		if self.errors > 0 {
+			process::exit(1);
+		}
+	}
+}
+
+/// Prints the outcome of one file of a batch and counts it in `summary`.
+///
+/// Files that are not DEX files are skipped with a note on stderr, unless `strict` is set.
+/// `path` is printed with the results and `origin`, where the path came from, with errors.
+fn report_file_result(
+	path: &str,
+	origin: &str,
+	result: &FileResult,
+	strict: bool,
+	json: bool,
+	dry_run: bool,
+	summary: &mut Summary,
+) {
+	match &result.error {
+		Some(FileError::NotADex(reason)) if !strict => {
+			eprintln!("{}: skipped, {}.", origin, reason);
+			return;
+		}
+		Some(e) => {
+			eprintln!("{}: {}", origin, e);
+			summary.errors += 1;
+			return;
+		}
+		None if result.changed => summary.corrected += 1,
+		None => summary.unchanged += 1,
+	}
+	if json {
+		let (current, expected) = (result.old_checksum, result.new_checksum);
+		let mut object = JsonObject::new()
+			.string("current", &current.map(format_u32).unwrap_or_default())
+			.string("expected", &expected.map(format_u32).unwrap_or_default())
+			.bool("valid", current == expected);
+		if let (Some(current), Some(expected)) = (result.old_signature, result.new_signature) {
+			object = object
+				.string("signature_current", &format_hex(&current))
+				.string("signature_expected", &format_hex(&expected))
+				.bool("signature_valid", current == expected);
+		}
+		let object = object.string("path", path).bool("changed", result.changed);
+		println!("{}", object);
+	} else if result.changed && dry_run {
+		report!("{}: would correct.", path);
+	} else if result.changed {
+		report!("{}: done.", path);
+	} else {
+		report!("{}: nothing to do.", path);
+	}
+}
+
+/// Logs the checksums of one file of a batch and where it was written, for `--verbose`.
+fn trace_file_result(path: &str, result: &FileResult, dry_run: bool) {
+	if let (Some(current), Some(expected)) = (result.old_checksum, result.new_checksum) {
+		verbose!(
+			"{}: current checksum {}, expected {}, {}",
+			path,
+			format_u32(current),
+			format_u32(expected),
+			if result.changed { "mismatch" } else { "match" }
+		);
+	}
+	if let (Some(current), Some(expected)) = (result.old_signature, result.new_signature) {
+		verbose!(
+			"{}: current signature {}, expected {}, {}",
+			path,
+			format_hex(&current),
+			format_hex(&expected),
+			if current == expected {
+				"match"
+			} else {
+				"mismatch"
+			}
+		);
+	}
+	if result.changed && !dry_run && result.error.is_none() {
+		verbose!("{}: wrote checksum", path);
+	}
+	match &result.output {
+		Some(output) if output == Path::new(path) => verbose!("{}: saved in place", path),
+		Some(output) => verbose!("{}: saved to {}", path, output.display()),
+		None => {}
+	}
+}
+
+/// A `processed N/M` counter on stderr, so that it never mixes with the results on stdout.
+struct Progress {
+	enabled: bool,
+	total: usize,
+	processed: usize,
+}
+
+impl Progress {
+	fn new(enabled: bool, total: usize) -> Self {
+		Progress {
+			enabled,
+			total,
+			processed: 0,
+		}
+	}
+
+	/// Counts one more file as processed, however it was handled.
+	fn tick(&mut self) {
+		self.processed += 1;
+		if self.enabled {
+			eprintln!("processed {}/{}", self.processed, self.total);
+		}
+	}
+}
+
+/// Reads a list of paths, one per line, ignoring blank lines and `#` comments.
+///
+/// # Returns
+/// Each path along with its `list:line` location, to report where a failing path came from.
+fn read_file_list(list: &str) -> Vec<(String, String)> {
+	let contents =
+		fs::read_to_string(list).unwrap_or_else(|e| fail(format!("Failed to read {}: {}", list, e)));
+	contents
+		.lines()
+		.enumerate()
+		.map(|(index, line)| (index + 1, line.trim()))
+		.filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+		.map(|(number, line)| {
+			let origin = format!("{}:{}: {}", list, number, line);
+			(line.to_string(), origin)
+		})
+		.collect()
+}
+
+/// Verifies every DEX file of `files`, given as pairs of a path and where it came from,
+/// exiting with a non-zero status if any is invalid.
+///
+/// If `junit` is given, a JUnit XML report of every file is written to it as well.
+fn verify_files(files: Vec<(String, String)>, signature: bool, json: bool, junit: Option<&str>) {
+	let mut all_valid = true;
+	let mut report = JunitReport::new("verify");
+	for (path, origin) in files {
+		let started = Instant::now();
+		let dex = match Dex::open(&path) {
+			Ok(dex) => dex,
+			Err(e) => {
+				eprintln!("{}: failed to read: {}", origin, e);
+				report.error(&path, started.elapsed(), &format!("failed to read: {}", e));
+				all_valid = false;
+				continue;
+			}
+		};
+		if let Err(e) = dex.validate_minimum_length() {
+			eprintln!("{}: {}", origin, e);
+			report.error(&path, started.elapsed(), &e.to_string());
+			all_valid = false;
+			continue;
+		}
+		let checksum_valid = dex.check_checksum();
+		let signature_valid = !signature || dex.check_signature();
+		all_valid &= checksum_valid && signature_valid;
+		let mut mismatches = Vec::new();
+		if !checksum_valid {
+			mismatches.push(format!(
+				"checksum mismatch: current {}, expected {}",
+				format_u32(dex.current_checksum_u32()),
+				format_u32(dex.expect_checksum_u32())
+			));
+		}
+		if !signature_valid {
+			mismatches.push(format!(
+				"signature mismatch: current {}, expected {}",
+				hex::encode(&dex.current_signature()),
+				hex::encode(&dex.expect_signature())
+			));
+		}
+		report.case(&path, started.elapsed(), &mismatches);
+		let status = |valid| match valid {
+			true => paint("ok", Style::Pass),
+			false => paint("mismatch", Style::Fail),
+		};
+		if json {
+			let mut object = checksum_json(&dex).string("path", &path);
+			if signature {
+				object = signature_json(object, &dex);
+			}
+			println!("{}", object);
+		} else if signature {
+			println!(
+				"{}: checksum {}, signature {}.",
+				path,
+				status(checksum_valid),
+				status(signature_valid)
+			);
+		} else {
+			println!("{}: checksum {}.", path, status(checksum_valid));
+		}
+	}
+	if let Some(junit) = junit {
+		report
+			.write_to_file(junit)
+			.unwrap_or_else(|e| fail(format!("Failed to write to {}: {}", junit, e)));
+		verbose!("wrote JUnit report to {}", junit);
+	}
+	if !all_valid {
+		process::exit(1);
+	}
+}
+
+/// A minimal JUnit XML writer for `verify --junit`, with one test case per file.
+struct JunitReport {
+	name: &'static str,
+	cases: Vec<String>,
+	failures: usize,
+	errors: usize,
+}
+
+impl JunitReport {
+	fn new(name: &'static str) -> Self {
+		JunitReport {
+			name,
+			cases: Vec::new(),
+			failures: 0,
+			errors: 0,
+		}
+	}
+
+	/// Adds a test case that fails with every message of `mismatches`, or passes if there are none.
+	fn case(&mut self, path: &str, elapsed: Duration, mismatches: &[String]) {
+		let body = match mismatches {
+			[] => String::new(),
+			mismatches => {
+				self.failures += 1;
+				format!(
+					"<failure message={}>{}</failure>",
+					xml_attribute(&mismatches.join("; ")),
+					xml_text(&mismatches.join("\n"))
+				)
+			}
+		};
+		self.push(path, elapsed, body);
+	}
+
+	/// Adds a test case for a file that could not be verified at all.
+	fn error(&mut self, path: &str, elapsed: Duration, message: &str) {
+		self.errors += 1;
+		self.push(
+			path,
+			elapsed,
+			format!("<error message={}/>", xml_attribute(message)),
+		);
+	}
+
+	fn push(&mut self, path: &str, elapsed: Duration, body: String) {
+		self.cases.push(format!(
+			"<testcase classname=\"dex_checksum_tools.{}\" name={} time=\"{:.3}\">{}</testcase>",
+			self.name,
+			xml_attribute(path),
+			elapsed.as_secs_f64(),
+			body
+		));
+	}
+
+	fn write_to_file(&self, path: &str) -> io::Result<()> {
+		let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+		xml.push_str(&format!(
+			"<testsuite name=\"dex_checksum_tools.{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\">\n",
+			self.name,
+			self.cases.len(),
+			self.failures,
+			self.errors
+		));
+		for case in &self.cases {
+			xml.push_str("  ");
+			xml.push_str(case);
+			xml.push('\n');
+		}
+		xml.push_str("</testsuite>\n");
+		fs::write(path, xml)
+	}
+}
+
+/// Escapes `value` as the quoted value of an XML attribute.
+fn xml_attribute(value: &str) -> String {
+	format!("\"{}\"", xml_text(value).replace('"', "&quot;"))
+}
+
+/// Escapes `value` as XML character data. Control characters, which XML 1.0 cannot hold
+/// even escaped, are replaced by `?`.
+fn xml_text(value: &str) -> String {
+	let mut escaped = String::with_capacity(value.len());
+	for c in value.chars() {
+		match c {
+			'&' => escaped.push_str("&amp;"),
+			'<' => escaped.push_str("&lt;"),
+			'>' => escaped.push_str("&gt;"),
+			'\n' | '\t' => escaped.push(c),
+			c if (c as u32) < 0x20 => escaped.push('?'),
+			c => escaped.push(c),
+		}
+	}
+	escaped
+}
+
+/// Makes sure that the directory an output file goes to exists, creating it if `mkdirs` is set.
+///
+/// Otherwise this fails with a message naming the missing directory, rather than the bare
+/// "No such file or directory" of creating the file.
+fn ensure_parent_dir(path: &Path, mkdirs: bool) {
+	let parent = match path.parent() {
+		Some(parent) if !parent.as_os_str().is_empty() && !parent.is_dir() => parent,
+		_ => return,
+	};
+	if !mkdirs {
+		fail(format!(
+			"Failed to write to {}: directory {} does not exist, pass --mkdirs to create it",
+			path.display(),
+			parent.display()
+		));
+	}
+	fs::create_dir_all(parent)
+		.unwrap_or_else(|e| fail(format!("Failed to create {}: {}", parent.display(), e)));
+	verbose!("created directory {}", parent.display());
+}
+
+/// Loads the input like [`load_input`], failing if it is too short to hold a DEX header.
+fn load_dex(input_dex_file: Option<String>, stdin_bytes: bool) -> (Option<String>, Dex) {
+	let (path, dex) = load_input(input_dex_file, stdin_bytes);
+	if let Err(e) = dex.validate_minimum_length() {
+		fail(format!("{}: {}", path.as_deref().unwrap_or("stdin"), e));
+	}
+	(path, dex)
+}
+
+/// Loads the input DEX file, returning its path unless its bytes were piped through stdin.
+fn load_input(input_dex_file: Option<String>, stdin_bytes: bool) -> (Option<String>, Dex) {
+	if stdin_bytes && matches!(input_dex_file.as_deref(), None | Some("-")) {
+		let dex = Dex::from_reader(stdin().lock())
+			.unwrap_or_else(|e| fail(format!("Failed to read from stdin: {}", e)));
+		trace_read("stdin", &dex);
+		return (None, dex);
+	}
+	let path = input_path(input_dex_file);
+	let dex = Dex::open(&path).unwrap_or_else(|e| fail(format!("Failed to read {}: {}", path, e)));
+	trace_read(&path, &dex);
+	(Some(path), dex)
+}
+
+/// Logs the size and magic of a DEX file that was just read, for `--verbose`.
+fn trace_read(origin: &str, dex: &Dex) {
+	let magic = &dex.as_bytes()[..dex.len().min(8)];
+	verbose!(
+		"{}: read {} bytes, magic {}",
+		origin,
+		dex.len(),
+		format_escaped(magic)
+	);
+}
+
+/// Logs the current and expected checksum of a DEX file, for `--verbose`.
+fn trace_checksum(origin: &str, dex: &Dex) {
+	// Computing the expected checksum is a full pass over the file, only worth it when logged.
+	if VERBOSE.load(Ordering::Relaxed) && dex.try_expect_checksum().is_ok() {
+		verbose!(
+			"{}: current checksum {}, expected {}, {}",
+			origin,
+			format_u32(dex.current_checksum_u32()),
+			format_u32(dex.expect_checksum_u32()),
+			if dex.check_checksum() {
+				"match"
+			} else {
+				"mismatch"
+			}
+		);
+	}
+}
+
+/// Logs the current and expected signature of a DEX file, for `--verbose`.
+fn trace_signature(origin: &str, dex: &Dex) {
+	if !VERBOSE.load(Ordering::Relaxed) {
+		return;
+	}
+	if let Ok(expected) = dex.try_expect_signature() {
+		verbose!(
+			"{}: current signature {}, expected {}, {}",
+			origin,
+			format_hex(&dex.current_signature()),
+			format_hex(&expected),
+			if dex.check_signature() {
+				"match"
+			} else {
+				"mismatch"
+			}
+		);
+	}
+}
+
+/// Parses the command line, with the options of `DEX_CHECKSUM_TOOLS_OPTS` as defaults.
+///
+/// Default options are added after the command line's own arguments, one at a time. A default
+/// is dropped if adding it makes clap reject the command line: because the command line gives
+/// the same option, under its short or long name, or one it conflicts with, so that the command
+/// line always wins; or because the subcommand does not accept it, so that defaults such as
+/// `--backup` do not break the subcommands without it.
+///
+/// # Returns
+/// The parsed arguments, the default options that were added and those that were not
+/// accepted. Defaults overridden by the command line are in neither.
+fn parse_args() -> (Args, Vec<String>, Vec<String>) {
+	let args: Vec<String> = env::args().collect();
+	let end = args
+		.iter()
+		.position(|arg| arg == "--")
+		.unwrap_or(args.len());
+	let parse = |defaults: &[String]| {
+		let mut merged = args.clone();
+		merged.splice(end..end, defaults.iter().cloned());
+		Args::clap().get_matches_from_safe(merged)
+	};
+	let mut added: Vec<String> = Vec::new();
+	let mut ignored = Vec::new();
+	for option in default_options() {
+		let before = parse(&added).err().map(|error| error.kind);
+		added.push(option);
+		let kind = match parse(&added) {
+			Ok(_) => continue,
+			Err(error) => error.kind,
+		};
+		// An error the command line already had on its own is not the default's fault.
+		if before == Some(kind) {
+			continue;
+		}
+		match kind {
+			ErrorKind::UnknownArgument => ignored.extend(added.pop()),
+			ErrorKind::ArgumentConflict | ErrorKind::UnexpectedMultipleUsage => {
+				added.pop();
+			}
+			_ => {}
+		}
+	}
+	match parse(&added) {
+		Ok(matches) => (Args::from_clap(&matches), added, ignored),
+		Err(error) => error.exit(),
+	}
+}
+
+/// Reads the options of `DEX_CHECKSUM_TOOLS_OPTS`, separated by whitespace.
+///
+/// Only options are allowed, with their values attached with `=` as in `--color=always`, so
+/// that they cannot be mistaken for the inputs and outputs of the command line.
+fn default_options() -> Vec<String> {
+	let value = match env::var(DEFAULT_OPTIONS_VAR) {
+		Ok(value) => value,
+		Err(env::VarError::NotPresent) => return Vec::new(),
+		Err(e) => fail(format!("Failed to read {}: {}", DEFAULT_OPTIONS_VAR, e)),
+	};
+	let options: Vec<String> = value.split_whitespace().map(String::from).collect();
+	if let Some(argument) = options.iter().find(|option| !option.starts_with('-')) {
+		fail(format!(
+			"{} may only hold options, with values attached as in --color=always, but holds {}",
+			DEFAULT_OPTIONS_VAR, argument
+		));
+	}
+	options
+}
+
+/// Prints an error the user can act on to stderr and exits with a non-zero status.
+fn fail(message: String) -> ! {
+	eprintln!("{}", message);
+	process::exit(1)
+}
+
+/// Resolves the input path argument, reading the path from stdin when it is omitted or "-".
+fn input_path(input_dex_file: Option<String>) -> String {
+	match input_dex_file.as_deref() {
+		None | Some("-") => {
+			let mut path = String::new();
+			let stdin = stdin();
+			let mut handle = stdin.lock();
+			handle
+				.read_to_string(&mut path)
+				.unwrap_or_else(|e| fail(format!("Failed to read the input path from stdin: {}", e)));
+			path.trim().to_string()
+		}
+		Some(path) => path.to_string(),
+	}
+}
+
+/// The outcome of one of the checks run by the `diff` subcommand.
+struct Check {
+	name: &'static str,
+	pass: bool,
+	current: String,
+	expected: String,
+}
+
+/// Runs every check on the DEX file, without panicking on truncated files.
+fn diagnose(dex: &Dex) -> Vec<Check> {
+	let unavailable = || "-".to_string();
+	let magic = dex.as_bytes().get(0..8).unwrap_or(dex.as_bytes());
+	let version = dex.dex_version();
+	let checksum = dex.try_current_checksum().ok();
+	let expected_checksum = dex.try_expect_checksum().ok();
+	let signature = dex.try_current_signature().ok();
+	let expected_signature = dex.try_expect_signature().ok();
+	let declared_size = dex.header().ok().map(|header| header.file_size());
+	let map_consistent = dex.check_map_consistency().ok();
+	vec![
+		Check {
+			name: "magic",
+			pass: dex.is_valid_magic(),
+			current: format_escaped(magic),
+			expected: "dex\\n???\\x00".to_string(),
+		},
+		Check {
+			name: "version",
+			pass: version.is_some_and(|v| v.min_api_level().is_some()),
+			current: version.map_or_else(unavailable, |v| format!("{:03}", v.number())),
+			expected: "035, 037, 038 or 039".to_string(),
+		},
+		Check {
+			name: "checksum",
+			pass: checksum.is_some() && checksum == expected_checksum,
+			current: checksum.map_or_else(unavailable, |_| format_u32(dex.current_checksum_u32())),
+			expected: expected_checksum
+				.map_or_else(unavailable, |_| format_u32(dex.expect_checksum_u32())),
+		},
+		Check {
+			name: "signature",
+			pass: signature.is_some() && signature == expected_signature,
+			current: signature.map_or_else(unavailable, |s| format_hex(&s)),
+			expected: expected_signature.map_or_else(unavailable, |s| format_hex(&s)),
+		},
+		Check {
+			name: "file_size",
+			pass: dex.check_file_size(),
+			current: declared_size.map_or_else(unavailable, |size| size.to_string()),
+			expected: dex.len().to_string(),
+		},
+		Check {
+			name: "header_size",
+			pass: dex.check_header_size(),
+			current: dex.header_size().map_or_else(unavailable, format_u32),
+			expected: format_u32(0x70),
+		},
+		Check {
+			name: "map_list",
+			pass: map_consistent == Some(true),
+			current: match map_consistent {
+				Some(true) => "consistent".to_string(),
+				Some(false) => "inconsistent".to_string(),
+				None => unavailable(),
+			},
+			expected: "consistent".to_string(),
+		},
+	]
+}
+
+/// The value of a header field, formatted according to what it holds.
+enum HeaderValue {
+	Text(String),
+	Size(u32),
+	Offset(u32),
+}
+
+impl Display for HeaderValue {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			HeaderValue::Text(text) => write!(f, "{}", text),
+			HeaderValue::Size(n) => write!(f, "{}", n),
+			HeaderValue::Offset(n) => write!(f, "{:#x}", n),
+		}
+	}
+}
+
+/// Lists every header field in file order, with the version parsed out of the magic.
+fn header_fields(dex: &Dex, header: &DexHeader) -> Vec<(&'static str, HeaderValue)> {
+	use HeaderValue::{Offset, Size, Text};
+	let version = dex
+		.version()
+		.map_or("unknown".to_string(), |v| v.to_string());
+	vec![
+		("magic", Text(format_escaped(&header.magic()))),
+		("version", Text(version)),
+		("checksum", Text(format_u32(header.checksum()))),
+		("signature", Text(format_hex(&header.signature()))),
+		("file_size", Size(header.file_size())),
+		("header_size", Size(header.header_size())),
+		("endian_tag", Text(format_u32(header.endian_tag()))),
+		("link_size", Size(header.link_size())),
+		("link_off", Offset(header.link_off())),
+		("map_off", Offset(header.map_off())),
+		("string_ids_size", Size(header.string_ids_size())),
+		("string_ids_off", Offset(header.string_ids_off())),
+		("type_ids_size", Size(header.type_ids_size())),
+		("type_ids_off", Offset(header.type_ids_off())),
+		("proto_ids_size", Size(header.proto_ids_size())),
+		("proto_ids_off", Offset(header.proto_ids_off())),
+		("field_ids_size", Size(header.field_ids_size())),
+		("field_ids_off", Offset(header.field_ids_off())),
+		("method_ids_size", Size(header.method_ids_size())),
+		("method_ids_off", Offset(header.method_ids_off())),
+		("class_defs_size", Size(header.class_defs_size())),
+		("class_defs_off", Offset(header.class_defs_off())),
+		("data_size", Size(header.data_size())),
+		("data_off", Offset(header.data_off())),
+	]
+}
+
+/// Prints rows as a left-aligned table with the given column headers.
+fn print_table<const N: usize>(headers: [&str; N], rows: impl Iterator<Item = [String; N]>) {
+	let rows: Vec<[String; N]> = rows.collect();
+	let mut widths = headers.map(visible_len);
+	for row in &rows {
+		for (width, cell) in widths.iter_mut().zip(row) {
+			*width = (*width).max(visible_len(cell));
+		}
+	}
+	let format_row = |cells: Vec<&str>| {
+		let padded: Vec<String> = cells
+			.iter()
+			.zip(widths)
+			.map(|(cell, width)| format!("{}{}", cell, " ".repeat(width - visible_len(cell))))
+			.collect();
+		padded.join("  ").trim_end().to_string()
+	};
+	println!("{}", format_row(headers.to_vec()));
+	for row in &rows {
+		println!("{}", format_row(row.iter().map(String::as_str).collect()));
+	}
+}
+
+/// The length of `text` as displayed, not counting the escape sequences that color it.
+fn visible_len(text: &str) -> usize {
+	let mut len = 0;
+	let mut in_escape = false;
+	for c in text.chars() {
+		if in_escape {
+			in_escape = c != 'm';
+		} else if c == '\x1b' {
+			in_escape = true;
+		} else {
+			len += 1;
+		}
+	}
+	len
+}
+
+/// How a result is colored on a terminal.
+#[derive(Debug, Clone, Copy)]
+enum Style {
+	Pass,
+	Fail,
+	Warning,
+}
+
+/// Colors `text` for its style with ANSI escape sequences, unless color is disabled.
+fn paint(text: &str, style: Style) -> String {
+	if !COLOR.load(Ordering::Relaxed) {
+		return text.to_string();
+	}
+	let code = match style {
+		Style::Pass => "32",
+		Style::Fail => "31",
+		Style::Warning => "33",
+	};
+	format!("\x1b[{}m{}\x1b[0m", code, text)
+}
+
+fn checksum_json(dex: &Dex) -> JsonObject {
+	JsonObject::new()
+		.string("current", &format_u32(dex.current_checksum_u32()))
+		.string("expected", &format_u32(dex.expect_checksum_u32()))
+		.bool("valid", dex.check_checksum())
+}
+
+fn signature_json(object: JsonObject, dex: &Dex) -> JsonObject {
+	object
+		.string("signature_current", &format_hex(&dex.current_signature()))
+		.string("signature_expected", &format_hex(&dex.expect_signature()))
+		.bool("signature_valid", dex.check_signature())
+}
+
+fn format_u32(value: u32) -> String {
+	format!("{:#010x}", value)
+}
+
+fn format_hex(bytes: &[u8]) -> String {
+	hex::encode(bytes)
+}
+
+fn format_escaped(bytes: &[u8]) -> String {
+	bytes.escape_ascii().to_string()
+}
+
+/// A minimal JSON object writer for the `--json` output mode.
+struct JsonObject {
+	fields: Vec<String>,
+}
+
+impl JsonObject {
+	fn new() -> Self {
+		JsonObject { fields: Vec::new() }
+	}
+
+	fn string(mut self, key: &str, value: &str) -> Self {
+		self
+			.fields
+			.push(format!("{}:{}", json_string(key), json_string(value)));
+		self
+	}
+
+	fn bool(mut self, key: &str, value: bool) -> Self {
+		self.fields.push(format!("{}:{}", json_string(key), value));
+		self
+	}
+
+	fn raw(mut self, key: &str, value: String) -> Self {
+		self.fields.push(format!("{}:{}", json_string(key), value));
+		self
+	}
+}
+
+impl Display for JsonObject {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{{{}}}", self.fields.join(","))
+	}
+}
+
+fn json_string(value: &str) -> String {
+	let mut quoted = String::with_capacity(value.len() + 2);
+	quoted.push('"');
+	for c in value.chars() {
+		match c {
+			'"' => quoted.push_str("\\\""),
+			'\\' => quoted.push_str("\\\\"),
+			'\n' => quoted.push_str("\\n"),
+			'\r' => quoted.push_str("\\r"),
+			'\t' => quoted.push_str("\\t"),
+			c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+			c => quoted.push(c),
+		}
+	}
+	quoted.push('"');
+	quoted
+}
+
 #[derive(Debug, StructOpt)]
 enum Opt {
 	/// Calculates the current checksum from the DEX file's header.
 	CurrentChecksum {
 		/// The input dex file to read, or "-" indicating to read stdin. If omitted, stdin will be used.
 		input_dex_file: Option<String>,
+		/// How to print the checksum: "bytes" (e.g. [157, 28, 43, 122]), "hex" (e.g. 9d1c2b7a)
+		/// or "u32" (the value decoded according to the endian_tag). Ignored with --json.
+		#[structopt(long, default_value = "bytes", possible_values = &["bytes", "hex", "u32"])]
+		format: ChecksumFormat,
 	},
 
 	/// Calculates the expected checksum for the DEX file.
 	ExpectChecksum {
 		/// The input dex file to read, or "-" indicating to read stdin. If omitted, stdin will be used.
 		input_dex_file: Option<String>,
+		/// How to print the checksum: "bytes" (e.g. [157, 28, 43, 122]), "hex" (e.g. 9d1c2b7a)
+		/// or "u32" (the value decoded according to the endian_tag). Ignored with --json.
+		#[structopt(long, default_value = "bytes", possible_values = &["bytes", "hex", "u32"])]
+		format: ChecksumFormat,
+	},
+
+	/// Verifies the checksum in the DEX file header, exiting with a non-zero status if it is wrong.
+	Verify {
+		/// The input dex file to read, or "-" indicating to read stdin. If omitted, stdin will be used.
+		input_dex_file: Option<String>,
+		/// Also verifies the SHA-1 signature in the DEX file header.
+		#[structopt(long)]
+		signature: bool,
+		/// Reads the paths to process from a file, one per line. Blank lines and lines
+		/// starting with `#` are ignored.
+		#[structopt(long, value_name = "list", conflicts_with = "input-dex-file")]
+		files_from: Option<String>,
+		/// Verifies every `*.dex` file under a directory. Symbolic links are not followed.
+		#[structopt(long, value_name = "dir", conflicts_with = "input-dex-file")]
+		tree: Option<String>,
+		/// Also writes a JUnit XML report to a file, with one test case per file, which fails if
+		/// its checksum or signature is invalid. Requires `--files-from` or `--tree`.
+		#[structopt(long, value_name = "file")]
+		junit: Option<String>,
+	},
+
+	/// Runs every check on the DEX file and prints a table of current vs expected values.
+	Diff {
+		/// The input dex file to read, or "-" indicating to read stdin. If omitted, stdin will be used.
+		input_dex_file: Option<String>,
+	},
+
+	/// Prints every field of the DEX file header as a table.
+	Header {
+		/// The input dex file to read, or "-" indicating to read stdin. If omitted, stdin will be used.
+		input_dex_file: Option<String>,
 	},
 
 	/// Corrects the checksum in the DEX file header if it does not match the expected checksum.
 	CorrectChecksum {
 		/// The input dex file to read, or "-" indicating to read stdin. If omitted, stdin will be used.
 		input_dex_file: Option<String>,
-		/// The output file to write, If omitted, overwrites the input file.
+		/// The output file to write, or "-" indicating to write stdout. If omitted, overwrites the
+		/// input file, or writes stdout when the DEX bytes were read from stdin.
+		///
+		/// The output file is only written if the checksum was corrected, unless `--force` is given.
+		/// When the DEX bytes go to stdout, they are always written.
 		output_dex_file: Option<String>,
+		/// Reports whether a correction is needed, and what it would be, without writing anything.
+		#[structopt(long)]
+		dry_run: bool,
+		/// Writes the output file even if the checksum was already correct.
+		#[structopt(long, conflicts_with = "dry-run")]
+		force: bool,
+		/// Also corrects the SHA-1 signature, before the checksum that covers it.
+		#[structopt(long)]
+		signature: bool,
+		/// Keeps the modification time of files corrected in place. Their permissions are always kept.
+		#[structopt(long)]
+		preserve: bool,
+		/// Copies the file about to be overwritten, the input or an existing output file, to
+		/// `<file><suffix>` first, `.bak` unless a suffix is given as `--backup=<suffix>`. Fails
+		/// if the backup already exists, or if the output is stdout.
+		#[structopt(long, value_name = "suffix", require_equals = true)]
+		backup: Option<Option<String>>,
+		/// Adds a number to the backup, as in `input.dex.bak.1`, when the backup already exists.
+		#[structopt(long, requires = "backup")]
+		numbered_backups: bool,
+	},
+
+	/// Corrects the checksums of multiple DEX files in place.
+	CorrectAll {
+		/// The input dex files to correct.
+		#[structopt(required_unless = "files-from")]
+		input_dex_files: Vec<String>,
+		/// The number of files to process in parallel, or 0 to use one thread per CPU. Files are
+		/// spread over threads of the standard library, without a thread pool such as rayon.
+		#[structopt(long, short, default_value = "1")]
+		jobs: usize,
+		/// Also corrects the SHA-1 signature of every file, before the checksum that covers it.
+		#[structopt(long)]
+		signature: bool,
+		/// Reports `processed N/M` on stderr as files are processed.
+		#[structopt(long)]
+		progress: bool,
+		/// Also reads the paths to process from a file, one per line. Blank lines and lines
+		/// starting with `#` are ignored.
+		#[structopt(long, value_name = "list")]
+		files_from: Option<String>,
+		/// Keeps the modification time of files corrected in place. Their permissions are always kept.
+		#[structopt(long, conflicts_with = "out-dir")]
+		preserve: bool,
+		/// Fails on files without a DEX magic, such as CompactDex files, instead of skipping them.
+		#[structopt(long)]
+		strict: bool,
+		/// Writes every processed file under this directory, at the path it was given as, instead
+		/// of correcting it in place. The directory is created if needed.
+		#[structopt(long, value_name = "dir")]
+		out_dir: Option<String>,
 	},
+
+	/// Recursively corrects the checksums of all `*.dex` files under a directory in place.
+	CorrectTree {
+		/// The root directory to search. Symbolic links are not followed.
+		root_dir: String,
+		/// Reports what would be corrected without writing anything.
+		#[structopt(long)]
+		dry_run: bool,
+		/// Also corrects the SHA-1 signature of every file, before the checksum that covers it.
+		#[structopt(long)]
+		signature: bool,
+		/// Reports `processed N/M` on stderr as files are processed.
+		#[structopt(long)]
+		progress: bool,
+		/// Keeps the modification time of files corrected in place. Their permissions are always kept.
+		#[structopt(long, conflicts_with = "out-dir")]
+		preserve: bool,
+		/// Fails on files without a DEX magic, such as CompactDex files, instead of skipping them.
+		#[structopt(long)]
+		strict: bool,
+		/// Writes every processed file under this directory, at the same path relative to the
+		/// root directory, instead of correcting it in place. The directory is created if needed.
+		#[structopt(long, value_name = "dir", conflicts_with = "dry-run")]
+		out_dir: Option<String>,
+	},
+
+	/// Corrects the signatures and checksums of all `classes*.dex` entries inside an APK or AAB.
+	#[structopt(alias = "correct-aab")]
+	CorrectApk {
+		/// The input apk or aab file to read.
+		input_apk_file: String,
+		/// The output apk or aab file to write.
+		output_apk_file: String,
+		/// Stores the corrected DEX entries uncompressed, instead of keeping their compression method.
+		#[structopt(long)]
+		store: bool,
+	},
+
+	/// Verifies the signatures and checksums of all `classes*.dex` entries inside an APK or AAB,
+	/// exiting with a non-zero status if any entry is invalid.
+	#[structopt(alias = "verify-aab")]
+	VerifyApk {
+		/// The input apk or aab file to read.
+		input_apk_file: String,
+	},
+}
+
+/// How the `current-checksum` and `expect-checksum` subcommands print a checksum.
+#[derive(Debug, Clone, Copy)]
+enum ChecksumFormat {
+	Bytes,
+	Hex,
+	U32,
+}
+
+impl ChecksumFormat {
+	fn render(self, bytes: [u8; 4], value: u32) -> String {
+		match self {
+			ChecksumFormat::Bytes => format!("{:?}", bytes),
+			ChecksumFormat::Hex => hex::encode(&bytes),
+			ChecksumFormat::U32 => value.to_string(),
+		}
+	}
+}
+
+/// When to color pass and fail results, see `--color`.
+#[derive(Debug, Clone, Copy)]
+enum ColorChoice {
+	Always,
+	Auto,
+	Never,
+}
+
+impl ColorChoice {
+	/// Decides whether to color the output. `auto` colors it when stdout is a terminal and the
+	/// `NO_COLOR` environment variable is not set, see <https://no-color.org>.
+	fn enabled(self) -> bool {
+		match self {
+			ColorChoice::Always => true,
+			ColorChoice::Never => false,
+			ColorChoice::Auto => {
+				io::stdout().is_terminal() && env::var_os("NO_COLOR").map_or(true, |v| v.is_empty())
+			}
+		}
+	}
+}
+
+impl FromStr for ColorChoice {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"always" => Ok(ColorChoice::Always),
+			"auto" => Ok(ColorChoice::Auto),
+			"never" => Ok(ColorChoice::Never),
+			_ => Err(format!("unknown color choice: {}", s)),
+		}
+	}
+}
+
+impl FromStr for ChecksumFormat {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"bytes" => Ok(ChecksumFormat::Bytes),
+			"hex" => Ok(ChecksumFormat::Hex),
+			"u32" => Ok(ChecksumFormat::U32),
+			_ => Err(format!("unknown checksum format: {}", s)),
+		}
+	}
 }
 
 #[derive(Debug, StructOpt)]
@@ -114,6 +1573,46 @@ struct Args {
 	#[structopt(subcommand)]
 	opt: Opt,
 
+	/// Prints the results as JSON instead of human-readable text.
+	#[structopt(long, global = true)]
+	json: bool,
+
+	/// Reads the raw DEX bytes from stdin instead of a path when the input is omitted or "-".
+	/// The corrected bytes are then written to stdout unless an output file is given.
+	///
+	/// This is the default when stdin is not a terminal, such as a pipe.
+	#[structopt(long, global = true)]
+	stdin_bytes: bool,
+
+	/// Reads the path of the input from stdin when the input is omitted or "-", even when stdin
+	/// is not a terminal.
+	#[structopt(long, global = true, conflicts_with = "stdin-bytes")]
+	stdin_path: bool,
+
+	/// Logs every step of the work to stderr, such as the checksums found and the fields written.
+	#[structopt(long, global = true)]
+	verbose: bool,
+
+	/// Suppresses informational messages such as `done.` and `nothing to do.` and the summary
+	/// line, leaving only errors and the exit status. `--json` output is still printed.
+	#[structopt(long, global = true, conflicts_with = "verbose")]
+	quiet: bool,
+
+	/// Colors pass and fail results: `auto` colors them when stdout is a terminal and `NO_COLOR`
+	/// is not set. `--json` output is never colored.
+	#[structopt(
+		long,
+		global = true,
+		value_name = "when",
+		default_value = "auto",
+		possible_values = &["always", "auto", "never"]
+	)]
+	color: ColorChoice,
+
+	/// Creates the missing parent directories of output files instead of failing.
+	#[structopt(long, global = true)]
+	mkdirs: bool,
+
 	#[structopt(long, hidden = true)]
 	debug: bool,
 }
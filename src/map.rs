@@ -0,0 +1,168 @@
+use crate::header::DexHeader;
+use crate::slice_at;
+use crate::DexError;
+use crate::Endianness;
+
+/// The size in bytes of one `map_item` in a `map_list`.
+const MAP_ITEM_SIZE: usize = 12;
+
+/// A `MapItem` structure that describes one section of a DEX file, as listed in its `map_list`.
+///
+/// All integer fields are decoded according to the file's `endian_tag`.
+///
+/// # Examples
+///
+/// ```
+/// use dex_checksum_tools::Dex;
+///
+/// # #[cfg(feature = "std-fs")]
+/// if let Ok(dex) = Dex::open("/path/to/input.dex") {
+///     for item in dex.map_list().unwrap_or_default() {
+///         println!(
+///             "{} x{} at {:#x}",
+///             item.type_name().unwrap_or("unknown"),
+///             item.size(),
+///             item.offset()
+///         );
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MapItem {
+	type_code: u16,
+	size: u32,
+	offset: u32,
+}
+
+impl MapItem {
+	/// Decodes the `map_list` located at `map_off` in a DEX file.
+	pub(crate) fn parse_list(
+		bytes: &[u8],
+		endianness: Endianness,
+		map_off: u32,
+	) -> Result<Vec<MapItem>, DexError> {
+		let start = map_off as usize;
+		let count_bytes = slice_at(bytes, start, 4)?;
+		let count = endianness.decode_u32(count_bytes.try_into().unwrap()) as usize;
+		let items_len = count
+			.checked_mul(MAP_ITEM_SIZE)
+			.ok_or(DexError::OffsetOutOfRange {
+				start,
+				end: usize::MAX,
+				len: bytes.len(),
+			})?;
+		let items = slice_at(bytes, start.saturating_add(4), items_len)?;
+		Ok(
+			items
+				.chunks_exact(MAP_ITEM_SIZE)
+				.map(|item| MapItem {
+					type_code: endianness.decode_u16(item[0..2].try_into().unwrap()),
+					size: endianness.decode_u32(item[4..8].try_into().unwrap()),
+					offset: endianness.decode_u32(item[8..12].try_into().unwrap()),
+				})
+				.collect(),
+		)
+	}
+
+	/// Checks that a decoded `map_list` describes the layout of the file consistently.
+	///
+	/// The sections must start with the header at offset 0 and be listed once each, in
+	/// increasing order of offset, ending with the `map_list` itself at `map_off`, which ends the
+	/// data section. Sections of fixed-size items must not run into the next section. The sizes of
+	/// other sections are only known by decoding them, so they are not checked.
+	pub(crate) fn is_consistent_list(items: &[MapItem], header: &DexHeader) -> bool {
+		let (first, last) = match (items.first(), items.last()) {
+			(Some(first), Some(last)) => (first, last),
+			_ => return false,
+		};
+		if first.type_code != 0x0000 || first.offset != 0 {
+			return false;
+		}
+		if last.type_code != 0x1000 || last.offset != header.map_off() {
+			return false;
+		}
+		// `map_list.size` comes from the file, so duplicates are found in one pass, not pairwise.
+		let mut seen = vec![false; 0x10000];
+		for item in items {
+			if std::mem::replace(&mut seen[item.type_code as usize], true) {
+				return false;
+			}
+		}
+		for pair in items.windows(2) {
+			let (item, next) = (pair[0], pair[1]);
+			if next.offset <= item.offset {
+				return false;
+			}
+			let end = item
+				.fixed_size_items()
+				.map(|size| item.offset as u64 + size * item.size as u64);
+			if end.is_some_and(|end| end > next.offset as u64) {
+				return false;
+			}
+		}
+		let map_end = last.offset as u64 + 4 + (items.len() * MAP_ITEM_SIZE) as u64;
+		let data_end = header.data_off() as u64 + header.data_size() as u64;
+		last.offset >= header.data_off() && map_end == data_end
+	}
+
+	/// The size in bytes of each item of the section, if its items all have the same size.
+	fn fixed_size_items(&self) -> Option<u64> {
+		let size = match self.type_code {
+			0x0000 => 0x70,
+			0x0001 | 0x0002 | 0x0007 => 4,
+			0x0003 => 12,
+			0x0004 | 0x0005 | 0x0008 => 8,
+			0x0006 => 32,
+			_ => return None,
+		};
+		Some(size)
+	}
+
+	/// The type code of the section, e.g. `0x0001` for `TYPE_STRING_ID_ITEM`.
+	pub fn type_code(&self) -> u16 {
+		self.type_code
+	}
+
+	/// The number of items in the section.
+	pub fn size(&self) -> u32 {
+		self.size
+	}
+
+	/// The offset of the section from the start of the file.
+	pub fn offset(&self) -> u32 {
+		self.offset
+	}
+
+	/// The name of the section type, e.g. `"string_id_item"`.
+	///
+	/// # Returns
+	/// - `Some(name)` if the type code is one defined by the DEX format.
+	/// - `None` otherwise.
+	pub fn type_name(&self) -> Option<&'static str> {
+		let name = match self.type_code {
+			0x0000 => "header_item",
+			0x0001 => "string_id_item",
+			0x0002 => "type_id_item",
+			0x0003 => "proto_id_item",
+			0x0004 => "field_id_item",
+			0x0005 => "method_id_item",
+			0x0006 => "class_def_item",
+			0x0007 => "call_site_id_item",
+			0x0008 => "method_handle_item",
+			0x1000 => "map_list",
+			0x1001 => "type_list",
+			0x1002 => "annotation_set_ref_list",
+			0x1003 => "annotation_set_item",
+			0x2000 => "class_data_item",
+			0x2001 => "code_item",
+			0x2002 => "string_data_item",
+			0x2003 => "debug_info_item",
+			0x2004 => "annotation_item",
+			0x2005 => "encoded_array_item",
+			0x2006 => "annotations_directory_item",
+			0xf000 => "hiddenapi_class_data_item",
+			_ => return None,
+		};
+		Some(name)
+	}
+}
@@ -0,0 +1,54 @@
+//! A read-only memory map of a file, available with the `memmap` feature on Unix.
+
+use std::fs::File;
+use std::io;
+use std::ops::Deref;
+use std::os::unix::io::AsRawFd;
+use std::ptr;
+use std::slice;
+
+pub(crate) struct Mmap {
+	ptr: *mut libc::c_void,
+	len: usize,
+}
+
+// The mapping is private and read-only, so it can be shared freely between threads.
+unsafe impl Send for Mmap {}
+unsafe impl Sync for Mmap {}
+
+impl Mmap {
+	/// Maps `len` bytes of `file` read-only. `len` must not be zero.
+	pub(crate) fn map(file: &File, len: usize) -> io::Result<Mmap> {
+		let ptr = unsafe {
+			libc::mmap(
+				ptr::null_mut(),
+				len,
+				libc::PROT_READ,
+				libc::MAP_PRIVATE,
+				file.as_raw_fd(),
+				0,
+			)
+		};
+		if ptr == libc::MAP_FAILED {
+			Err(io::Error::last_os_error())
+		} else {
+			Ok(Mmap { ptr, len })
+		}
+	}
+}
+
+impl Deref for Mmap {
+	type Target = [u8];
+
+	fn deref(&self) -> &[u8] {
+		unsafe { slice::from_raw_parts(self.ptr as *const u8, self.len) }
+	}
+}
+
+impl Drop for Mmap {
+	fn drop(&mut self) {
+		unsafe {
+			libc::munmap(self.ptr, self.len);
+		}
+	}
+}
@@ -0,0 +1,119 @@
+//! A small, dependency-free SHA-1 implementation used for the DEX signature.
+
+/// An incremental SHA-1 hasher.
+#[derive(Clone)]
+pub struct Sha1 {
+	state: [u32; 5],
+	buffer: [u8; 64],
+	buffered: usize,
+	length: u64,
+}
+
+impl Default for Sha1 {
+	/// Creates a hasher in its initial state, like [`Sha1::new`].
+	fn default() -> Self {
+		Sha1::new()
+	}
+}
+
+impl Sha1 {
+	/// Creates a hasher in its initial state.
+	pub fn new() -> Self {
+		Sha1 {
+			state: [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0],
+			buffer: [0; 64],
+			buffered: 0,
+			length: 0,
+		}
+	}
+
+	/// Feeds `data` into the hasher.
+	pub fn update(&mut self, mut data: &[u8]) {
+		self.length = self.length.wrapping_add(data.len() as u64);
+		if self.buffered > 0 {
+			let take = (64 - self.buffered).min(data.len());
+			self.buffer[self.buffered..self.buffered + take].copy_from_slice(&data[..take]);
+			self.buffered += take;
+			data = &data[take..];
+			if self.buffered < 64 {
+				return;
+			}
+			let block = self.buffer;
+			self.compress(&block);
+			self.buffered = 0;
+		}
+		let mut blocks = data.chunks_exact(64);
+		for block in &mut blocks {
+			self.compress(block.try_into().unwrap());
+		}
+		let rest = blocks.remainder();
+		self.buffer[..rest.len()].copy_from_slice(rest);
+		self.buffered = rest.len();
+	}
+
+	/// Consumes the hasher and returns the 20-byte digest.
+	pub fn finalize(mut self) -> [u8; 20] {
+		let bit_length = self.length.wrapping_mul(8);
+		let mut padding = [0u8; 72];
+		padding[0] = 0x80;
+		let pad_length = if self.buffered < 56 {
+			56 - self.buffered
+		} else {
+			120 - self.buffered
+		};
+		padding[pad_length..pad_length + 8].copy_from_slice(&bit_length.to_be_bytes());
+		let length = self.length;
+		self.update(&padding[..pad_length + 8]);
+		self.length = length;
+
+		let mut digest = [0u8; 20];
+		for (chunk, word) in digest.chunks_exact_mut(4).zip(self.state.iter()) {
+			chunk.copy_from_slice(&word.to_be_bytes());
+		}
+		digest
+	}
+
+	fn compress(&mut self, block: &[u8; 64]) {
+		let mut w = [0u32; 80];
+		for (i, chunk) in block.chunks_exact(4).enumerate() {
+			w[i] = u32::from_be_bytes(chunk.try_into().unwrap());
+		}
+		for i in 16..80 {
+			w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+		}
+
+		let [mut a, mut b, mut c, mut d, mut e] = self.state;
+		for (i, word) in w.iter().enumerate() {
+			let (f, k) = match i {
+				0..=19 => ((b & c) | (!b & d), 0x5A827999),
+				20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+				40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+				_ => (b ^ c ^ d, 0xCA62C1D6),
+			};
+			let temp = a
+				.rotate_left(5)
+				.wrapping_add(f)
+				.wrapping_add(e)
+				.wrapping_add(k)
+				.wrapping_add(*word);
+			e = d;
+			d = c;
+			c = b.rotate_left(30);
+			b = a;
+			a = temp;
+		}
+
+		self.state[0] = self.state[0].wrapping_add(a);
+		self.state[1] = self.state[1].wrapping_add(b);
+		self.state[2] = self.state[2].wrapping_add(c);
+		self.state[3] = self.state[3].wrapping_add(d);
+		self.state[4] = self.state[4].wrapping_add(e);
+	}
+}
+
+/// Computes the SHA-1 digest of `data` in one call.
+pub fn sha1(data: &[u8]) -> [u8; 20] {
+	let mut hasher = Sha1::new();
+	hasher.update(data);
+	hasher.finalize()
+}
@@ -0,0 +1,139 @@
+//! A small, dependency-free SHA-256 implementation used for content hashes.
+
+/// The round constants: the first 32 bits of the fractional parts of the cube roots of the
+/// first 64 primes.
+const K: [u32; 64] = [
+	0x428A2F98, 0x71374491, 0xB5C0FBCF, 0xE9B5DBA5, 0x3956C25B, 0x59F111F1, 0x923F82A4, 0xAB1C5ED5,
+	0xD807AA98, 0x12835B01, 0x243185BE, 0x550C7DC3, 0x72BE5D74, 0x80DEB1FE, 0x9BDC06A7, 0xC19BF174,
+	0xE49B69C1, 0xEFBE4786, 0x0FC19DC6, 0x240CA1CC, 0x2DE92C6F, 0x4A7484AA, 0x5CB0A9DC, 0x76F988DA,
+	0x983E5152, 0xA831C66D, 0xB00327C8, 0xBF597FC7, 0xC6E00BF3, 0xD5A79147, 0x06CA6351, 0x14292967,
+	0x27B70A85, 0x2E1B2138, 0x4D2C6DFC, 0x53380D13, 0x650A7354, 0x766A0ABB, 0x81C2C92E, 0x92722C85,
+	0xA2BFE8A1, 0xA81A664B, 0xC24B8B70, 0xC76C51A3, 0xD192E819, 0xD6990624, 0xF40E3585, 0x106AA070,
+	0x19A4C116, 0x1E376C08, 0x2748774C, 0x34B0BCB5, 0x391C0CB3, 0x4ED8AA4A, 0x5B9CCA4F, 0x682E6FF3,
+	0x748F82EE, 0x78A5636F, 0x84C87814, 0x8CC70208, 0x90BEFFFA, 0xA4506CEB, 0xBEF9A3F7, 0xC67178F2,
+];
+
+/// An incremental SHA-256 hasher.
+#[derive(Clone)]
+pub struct Sha256 {
+	state: [u32; 8],
+	buffer: [u8; 64],
+	buffered: usize,
+	length: u64,
+}
+
+impl Default for Sha256 {
+	/// Creates a hasher in its initial state, like [`Sha256::new`].
+	fn default() -> Self {
+		Sha256::new()
+	}
+}
+
+impl Sha256 {
+	/// Creates a hasher in its initial state.
+	pub fn new() -> Self {
+		Sha256 {
+			state: [
+				0x6A09E667, 0xBB67AE85, 0x3C6EF372, 0xA54FF53A, 0x510E527F, 0x9B05688C, 0x1F83D9AB,
+				0x5BE0CD19,
+			],
+			buffer: [0; 64],
+			buffered: 0,
+			length: 0,
+		}
+	}
+
+	/// Feeds `data` into the hasher.
+	pub fn update(&mut self, mut data: &[u8]) {
+		self.length = self.length.wrapping_add(data.len() as u64);
+		if self.buffered > 0 {
+			let take = (64 - self.buffered).min(data.len());
+			self.buffer[self.buffered..self.buffered + take].copy_from_slice(&data[..take]);
+			self.buffered += take;
+			data = &data[take..];
+			if self.buffered < 64 {
+				return;
+			}
+			let block = self.buffer;
+			self.compress(&block);
+			self.buffered = 0;
+		}
+		let mut blocks = data.chunks_exact(64);
+		for block in &mut blocks {
+			self.compress(block.try_into().unwrap());
+		}
+		let rest = blocks.remainder();
+		self.buffer[..rest.len()].copy_from_slice(rest);
+		self.buffered = rest.len();
+	}
+
+	/// Consumes the hasher and returns the 32-byte digest.
+	pub fn finalize(mut self) -> [u8; 32] {
+		let bit_length = self.length.wrapping_mul(8);
+		let mut padding = [0u8; 72];
+		padding[0] = 0x80;
+		let pad_length = if self.buffered < 56 {
+			56 - self.buffered
+		} else {
+			120 - self.buffered
+		};
+		padding[pad_length..pad_length + 8].copy_from_slice(&bit_length.to_be_bytes());
+		let length = self.length;
+		self.update(&padding[..pad_length + 8]);
+		self.length = length;
+
+		let mut digest = [0u8; 32];
+		for (chunk, word) in digest.chunks_exact_mut(4).zip(self.state.iter()) {
+			chunk.copy_from_slice(&word.to_be_bytes());
+		}
+		digest
+	}
+
+	fn compress(&mut self, block: &[u8; 64]) {
+		let mut w = [0u32; 64];
+		for (i, chunk) in block.chunks_exact(4).enumerate() {
+			w[i] = u32::from_be_bytes(chunk.try_into().unwrap());
+		}
+		for i in 16..64 {
+			let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+			let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+			w[i] = w[i - 16]
+				.wrapping_add(s0)
+				.wrapping_add(w[i - 7])
+				.wrapping_add(s1);
+		}
+
+		let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = self.state;
+		for (word, k) in w.iter().zip(K.iter()) {
+			let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+			let ch = (e & f) ^ (!e & g);
+			let temp1 = h
+				.wrapping_add(s1)
+				.wrapping_add(ch)
+				.wrapping_add(*k)
+				.wrapping_add(*word);
+			let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+			let maj = (a & b) ^ (a & c) ^ (b & c);
+			let temp2 = s0.wrapping_add(maj);
+			h = g;
+			g = f;
+			f = e;
+			e = d.wrapping_add(temp1);
+			d = c;
+			c = b;
+			b = a;
+			a = temp1.wrapping_add(temp2);
+		}
+
+		for (state, word) in self.state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+			*state = state.wrapping_add(word);
+		}
+	}
+}
+
+/// Computes the SHA-256 digest of `data` in one call.
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+	let mut hasher = Sha256::new();
+	hasher.update(data);
+	hasher.finalize()
+}
@@ -0,0 +1,84 @@
+use std::io;
+use std::io::Read;
+
+use crate::adler::Adler32;
+use crate::Endianness;
+
+/// The number of bytes read up front: everything up to and including the `endian_tag`.
+const PREFIX_SIZE: usize = 44;
+
+/// The size of the window used to stream the rest of the file.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Verifies the checksum of a DEX file without holding the whole file in memory.
+///
+/// This function reads the header up to the `endian_tag`, captures the stored checksum,
+/// then streams the rest of the file through an incremental Adler-32, never buffering
+/// more than a small window. It is meant for verification at scale; use [`crate::Dex`]
+/// to correct a file.
+///
+/// # Arguments
+///
+/// * `reader` - Any source implementing `Read`, positioned at the start of the DEX file.
+///
+/// # Returns
+///
+/// An `io::Result<bool>` which is `Ok(true)` if the stored checksum matches the data,
+/// `Ok(false)` if it does not, or an `Err` if reading failed or the file is shorter than 12 bytes.
+///
+/// # Examples
+///
+/// ```
+/// use std::fs::File;
+///
+/// use dex_checksum_tools::verify_checksum_streaming;
+///
+/// if let Ok(file) = File::open("/path/to/input.dex") {
+///     match verify_checksum_streaming(file) {
+///         Ok(valid) => println!("checksum valid: {}", valid),
+///         Err(e) => println!("Failed to read file: {}", e),
+///     }
+/// }
+/// ```
+pub fn verify_checksum_streaming<R: Read>(mut reader: R) -> io::Result<bool> {
+	let mut prefix = [0u8; PREFIX_SIZE];
+	let prefix_len = read_up_to(&mut reader, &mut prefix)?;
+	if prefix_len < 12 {
+		return Err(io::Error::new(
+			io::ErrorKind::UnexpectedEof,
+			"DEX file is shorter than 12 bytes",
+		));
+	}
+	let endianness = match prefix_len {
+		PREFIX_SIZE if prefix[40..44] == [0x12, 0x34, 0x56, 0x78] => Endianness::Big,
+		_ => Endianness::Little,
+	};
+	let stored = endianness.decode_u32(prefix[8..12].try_into().unwrap());
+
+	let mut adler = Adler32::new();
+	adler.update(&prefix[12..prefix_len]);
+	let mut chunk = vec![0u8; CHUNK_SIZE];
+	loop {
+		match reader.read(&mut chunk) {
+			Ok(0) => break,
+			Ok(n) => adler.update(&chunk[..n]),
+			Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+			Err(e) => return Err(e),
+		}
+	}
+	Ok(adler.hash() == stored)
+}
+
+/// Fills `buffer` from `reader`, stopping early only at EOF.
+fn read_up_to<R: Read>(reader: &mut R, buffer: &mut [u8]) -> io::Result<usize> {
+	let mut filled = 0;
+	while filled < buffer.len() {
+		match reader.read(&mut buffer[filled..]) {
+			Ok(0) => break,
+			Ok(n) => filled += n,
+			Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+			Err(e) => return Err(e),
+		}
+	}
+	Ok(filled)
+}
@@ -0,0 +1,81 @@
+use crate::slice_at;
+use crate::DexError;
+use crate::Endianness;
+
+/// Decodes the string at `index` in the string identifiers list at `string_ids_off`.
+pub(crate) fn string_at(
+	bytes: &[u8],
+	endianness: Endianness,
+	string_ids_off: u32,
+	index: u32,
+) -> Result<String, DexError> {
+	let out_of_range = || DexError::OffsetOutOfRange {
+		start: string_ids_off as usize,
+		end: usize::MAX,
+		len: bytes.len(),
+	};
+	let id_off = (index as usize)
+		.checked_mul(4)
+		.and_then(|offset| offset.checked_add(string_ids_off as usize))
+		.ok_or_else(out_of_range)?;
+	let id = slice_at(bytes, id_off, 4)?;
+	let data_off = endianness.decode_u32(id.try_into().unwrap()) as usize;
+	let data = bytes.get(data_off..).ok_or(DexError::OffsetOutOfRange {
+		start: data_off,
+		end: data_off,
+		len: bytes.len(),
+	})?;
+	let invalid = || DexError::InvalidString { offset: data_off };
+	let (utf16_size, prefix_len) = read_uleb128(data).ok_or_else(invalid)?;
+	let units = decode_mutf8(&data[prefix_len..]).ok_or_else(invalid)?;
+	if units.len() != utf16_size as usize {
+		return Err(invalid());
+	}
+	String::from_utf16(&units).map_err(|_| invalid())
+}
+
+/// Reads an unsigned LEB128 value of at most 5 bytes, returning it along with its length.
+fn read_uleb128(bytes: &[u8]) -> Option<(u32, usize)> {
+	let mut value = 0u32;
+	for (i, &byte) in bytes.iter().take(5).enumerate() {
+		value |= ((byte & 0x7f) as u32) << (i * 7);
+		if byte & 0x80 == 0 {
+			return Some((value, i + 1));
+		}
+	}
+	None
+}
+
+/// Decodes NUL-terminated MUTF-8 into UTF-16 code units.
+///
+/// MUTF-8 differs from UTF-8 in encoding NUL as `c0 80` and supplementary characters as
+/// two separately encoded surrogates, so decoding to UTF-16 first handles both naturally.
+fn decode_mutf8(bytes: &[u8]) -> Option<Vec<u16>> {
+	let mut units = Vec::new();
+	let mut position = 0;
+	let continuation = |position: usize| match bytes.get(position) {
+		Some(&byte) if byte & 0xc0 == 0x80 => Some((byte & 0x3f) as u16),
+		_ => None,
+	};
+	loop {
+		let first = *bytes.get(position)? as u16;
+		match first {
+			0x00 => return Some(units),
+			0x01..=0x7f => {
+				units.push(first);
+				position += 1;
+			}
+			0xc0..=0xdf => {
+				units.push((first & 0x1f) << 6 | continuation(position + 1)?);
+				position += 2;
+			}
+			0xe0..=0xef => {
+				units.push(
+					(first & 0x0f) << 12 | continuation(position + 1)? << 6 | continuation(position + 2)?,
+				);
+				position += 3;
+			}
+			_ => return None,
+		}
+	}
+}
@@ -0,0 +1,69 @@
+/// The DEX format version, as read from the three ASCII digits of the magic.
+///
+/// # Examples
+///
+/// ```
+/// use dex_checksum_tools::Dex;
+/// use dex_checksum_tools::DexVersion;
+///
+/// # #[cfg(feature = "std-fs")]
+/// if let Ok(dex) = Dex::open("/path/to/input.dex") {
+///     match dex.dex_version().and_then(|version| version.min_api_level()) {
+///         Some(api_level) => println!("loadable from API level {}", api_level),
+///         None => println!("unknown DEX version"),
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DexVersion {
+	/// `dex\n035\0`, the original format.
+	V035,
+	/// `dex\n037\0`, which added default interface methods.
+	V037,
+	/// `dex\n038\0`, which added `invoke-polymorphic` and `invoke-custom`.
+	V038,
+	/// `dex\n039\0`, which added `const-method-handle` and `const-method-type`.
+	V039,
+	/// Any other version number.
+	Unknown(u32),
+}
+
+impl DexVersion {
+	/// The version number, e.g. `35` for [`DexVersion::V035`].
+	pub fn number(&self) -> u32 {
+		match self {
+			DexVersion::V035 => 35,
+			DexVersion::V037 => 37,
+			DexVersion::V038 => 38,
+			DexVersion::V039 => 39,
+			DexVersion::Unknown(number) => *number,
+		}
+	}
+
+	/// The lowest Android API level that can load this version. Every later level can load it too.
+	///
+	/// # Returns
+	/// - `Some(api_level)` for a known version.
+	/// - `None` for [`DexVersion::Unknown`].
+	pub fn min_api_level(&self) -> Option<u32> {
+		match self {
+			DexVersion::V035 => Some(1),
+			DexVersion::V037 => Some(24),
+			DexVersion::V038 => Some(26),
+			DexVersion::V039 => Some(28),
+			DexVersion::Unknown(_) => None,
+		}
+	}
+}
+
+impl From<u32> for DexVersion {
+	fn from(number: u32) -> Self {
+		match number {
+			35 => DexVersion::V035,
+			37 => DexVersion::V037,
+			38 => DexVersion::V038,
+			39 => DexVersion::V039,
+			number => DexVersion::Unknown(number),
+		}
+	}
+}
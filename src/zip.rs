@@ -0,0 +1,420 @@
+//! A minimal ZIP archive reader and writer, just enough to patch entries inside an APK.
+
+use std::io;
+use std::io::Write;
+
+use crate::crc32::crc32;
+use crate::deflate;
+use crate::DexError;
+
+const LOCAL_HEADER_SIGNATURE: u32 = 0x04034b50;
+const CENTRAL_HEADER_SIGNATURE: u32 = 0x02014b50;
+const END_OF_CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x06054b50;
+const DATA_DESCRIPTOR_SIGNATURE: u32 = 0x08074b50;
+
+/// The extra field ID zipalign pads local headers with, followed by the alignment and zeros.
+const ALIGNMENT_EXTRA_ID: u16 = 0xd935;
+/// The alignment of shared libraries in an APK, and the largest one zipalign uses. Entries
+/// that follow a rewritten entry are kept at the same offset modulo this.
+const PAGE_ALIGNMENT: usize = 4096;
+
+/// The compression method of an entry that is stored without compression.
+pub(crate) const METHOD_STORED: u16 = 0;
+/// The compression method of an entry that is compressed with DEFLATE.
+pub(crate) const METHOD_DEFLATED: u16 = 8;
+
+/// The flag bit indicating that an entry is encrypted.
+const FLAG_ENCRYPTED: u16 = 1 << 0;
+/// The flag bit indicating that sizes and CRC follow the data in a data descriptor.
+const FLAG_DATA_DESCRIPTOR: u16 = 1 << 3;
+
+/// Checks if `bytes` start with a local file header, as every non-empty ZIP archive does.
+pub(crate) fn is_zip(bytes: &[u8]) -> bool {
+	bytes.get(0..4) == Some(&LOCAL_HEADER_SIGNATURE.to_le_bytes()[..])
+}
+
+/// A single entry of a ZIP archive, holding its raw (possibly compressed) data.
+#[derive(Debug, Clone)]
+pub(crate) struct ZipEntry {
+	pub(crate) name: String,
+	raw_name: Vec<u8>,
+	version_made_by: u16,
+	version_needed: u16,
+	flags: u16,
+	pub(crate) method: u16,
+	time: u16,
+	date: u16,
+	crc32: u32,
+	uncompressed_size: u32,
+	local_extra: Vec<u8>,
+	central_extra: Vec<u8>,
+	comment: Vec<u8>,
+	internal_attributes: u16,
+	external_attributes: u32,
+	data: Vec<u8>,
+	/// The offset of the local header in the original archive.
+	offset: usize,
+	/// The original local header, data and data descriptor, written back as they are until
+	/// the contents are replaced.
+	record: Option<Vec<u8>>,
+	/// The length of the original record.
+	record_len: usize,
+	/// The compression method and data offset in the original archive.
+	original_method: u16,
+	original_data_offset: usize,
+	/// Bytes in front of the local header that belong to no entry, kept as they are.
+	gap: Vec<u8>,
+}
+
+impl ZipEntry {
+	/// Decompresses and returns the contents of the entry.
+	pub(crate) fn contents(&self) -> Result<Vec<u8>, DexError> {
+		if self.flags & FLAG_ENCRYPTED != 0 {
+			return Err(zip_error(format!("{} is encrypted", self.name)));
+		}
+		let contents = match self.method {
+			METHOD_STORED => self.data.clone(),
+			// Inflating stops at the declared size, so a crafted entry cannot exhaust memory.
+			METHOD_DEFLATED => deflate::inflate_limited(&self.data, self.uncompressed_size as usize)
+				.map_err(|e| zip_error(format!("{}: {}", self.name, e)))?,
+			method => {
+				return Err(zip_error(format!(
+					"{} uses unsupported compression method {}",
+					self.name, method
+				)))
+			}
+		};
+		if contents.len() != self.uncompressed_size as usize || crc32(&contents) != self.crc32 {
+			return Err(zip_error(format!("{} is corrupt", self.name)));
+		}
+		Ok(contents)
+	}
+
+	/// Replaces the contents of the entry, compressing them with the entry's own method.
+	///
+	/// Deflated entries are compressed again with fixed Huffman codes only, so they usually end
+	/// up larger than they were. The flags are kept as they are, so their compression level
+	/// bits no longer describe how the data was compressed.
+	pub(crate) fn set_contents(&mut self, contents: Vec<u8>) {
+		match self.method {
+			METHOD_DEFLATED => {
+				self.crc32 = crc32(&contents);
+				self.uncompressed_size = contents.len() as u32;
+				self.data = deflate::deflate(&contents);
+				self.record = None;
+			}
+			_ => self.set_contents_stored(contents),
+		}
+	}
+
+	/// Replaces the contents of the entry, storing them without compression.
+	pub(crate) fn set_contents_stored(&mut self, contents: Vec<u8>) {
+		self.method = METHOD_STORED;
+		self.crc32 = crc32(&contents);
+		self.uncompressed_size = contents.len() as u32;
+		self.data = contents;
+		self.record = None;
+	}
+
+	/// The flags as written: a rewritten entry never has a data descriptor.
+	fn written_flags(&self) -> u16 {
+		match self.record {
+			Some(_) => self.flags,
+			None => self.flags & !FLAG_DATA_DESCRIPTOR,
+		}
+	}
+
+	/// Writes a new local header for the entry, followed by its data, at the end of `out`.
+	fn write_local_record(&self, out: &mut Vec<u8>) {
+		// The old alignment field no longer aligns anything, a fresh one is added if needed.
+		let local_extra = without_alignment_field(&self.local_extra);
+		let header_len = 30 + self.raw_name.len() + local_extra.len();
+		let (padding, gap) = self.padding(out.len(), header_len + self.data.len());
+		out.extend_from_slice(&LOCAL_HEADER_SIGNATURE.to_le_bytes());
+		out.extend_from_slice(&self.version_needed.to_le_bytes());
+		out.extend_from_slice(&self.written_flags().to_le_bytes());
+		out.extend_from_slice(&self.method.to_le_bytes());
+		out.extend_from_slice(&self.time.to_le_bytes());
+		out.extend_from_slice(&self.date.to_le_bytes());
+		out.extend_from_slice(&self.crc32.to_le_bytes());
+		out.extend_from_slice(&(self.data.len() as u32).to_le_bytes());
+		out.extend_from_slice(&self.uncompressed_size.to_le_bytes());
+		out.extend_from_slice(&(self.raw_name.len() as u16).to_le_bytes());
+		out.extend_from_slice(&((local_extra.len() + padding) as u16).to_le_bytes());
+		out.extend_from_slice(&self.raw_name);
+		out.extend_from_slice(&local_extra);
+		if padding > 0 {
+			out.extend_from_slice(&ALIGNMENT_EXTRA_ID.to_le_bytes());
+			out.extend_from_slice(&(padding as u16 - 4).to_le_bytes());
+			let mut payload = vec![0; padding - 4];
+			if let Some(alignment) = payload.get_mut(0..2) {
+				alignment.copy_from_slice(&4u16.to_le_bytes());
+			}
+			out.extend_from_slice(&payload);
+		}
+		out.extend_from_slice(&self.data);
+		out.resize(out.len() + gap, 0);
+	}
+
+	/// Chooses the padding of a rewritten entry that starts at `start` and is `len` bytes long
+	/// without it.
+	///
+	/// The padding makes the entry end where it originally did modulo [`PAGE_ALIGNMENT`], so
+	/// that the entries after it keep their zipalign alignment, and keeps the data of a stored
+	/// entry at its original alignment modulo 4 (or 4-byte aligned, if its data was deflated).
+	/// It goes into the local extra field, as an extra field of its own, so it is either 0 or
+	/// at least 4 bytes. When that cannot do both, the rest goes after the data as zeros that
+	/// belong to no entry, which ZIP readers skip since they locate entries through the
+	/// central directory.
+	///
+	/// # Returns
+	/// The padding of the local extra field, and the number of zeros after the data.
+	fn padding(&self, start: usize, len: usize) -> (usize, usize) {
+		let data_start = start + len - self.data.len();
+		let data_alignment = match self.original_method {
+			METHOD_STORED => self.original_data_offset % 4,
+			_ => 0,
+		};
+		let aligned =
+			|padding: usize| self.method != METHOD_STORED || (data_start + padding) % 4 == data_alignment;
+		let keeps_layout = |padding: usize| {
+			(start + len + padding) % PAGE_ALIGNMENT == (self.offset + self.record_len) % PAGE_ALIGNMENT
+		};
+		let candidates = || std::iter::once(0).chain(4..PAGE_ALIGNMENT + 4);
+		if let Some(padding) = candidates().find(|&padding| aligned(padding) && keeps_layout(padding)) {
+			return (padding, 0);
+		}
+		let padding = candidates().find(|&padding| aligned(padding)).unwrap_or(0);
+		let end = (start + len + padding) % PAGE_ALIGNMENT;
+		let original_end = (self.offset + self.record_len) % PAGE_ALIGNMENT;
+		(
+			padding,
+			(original_end + PAGE_ALIGNMENT - end) % PAGE_ALIGNMENT,
+		)
+	}
+}
+
+/// Removes the zipalign alignment fields ([`ALIGNMENT_EXTRA_ID`]) from a local extra field.
+///
+/// Any other field is kept, and so is a malformed tail that cannot be split into fields.
+fn without_alignment_field(extra: &[u8]) -> Vec<u8> {
+	let mut kept = Vec::with_capacity(extra.len());
+	let mut rest = extra;
+	while rest.len() >= 4 {
+		let id = u16::from_le_bytes([rest[0], rest[1]]);
+		let len = 4 + u16::from_le_bytes([rest[2], rest[3]]) as usize;
+		if len > rest.len() {
+			break;
+		}
+		if id != ALIGNMENT_EXTRA_ID {
+			kept.extend_from_slice(&rest[..len]);
+		}
+		rest = &rest[len..];
+	}
+	kept.extend_from_slice(rest);
+	kept
+}
+
+/// A ZIP archive held fully in memory.
+#[derive(Debug, Clone)]
+pub(crate) struct ZipArchive {
+	pub(crate) entries: Vec<ZipEntry>,
+	/// Bytes between the last entry and the central directory, such as an APK Signing Block.
+	before_central_directory: Vec<u8>,
+	comment: Vec<u8>,
+}
+
+impl ZipArchive {
+	/// Parses a ZIP archive from its bytes, using the central directory as the source of truth.
+	///
+	/// ZIP64 archives and multi-disk archives are not supported.
+	pub(crate) fn parse(bytes: &[u8]) -> Result<ZipArchive, DexError> {
+		let eocd = find_end_of_central_directory(bytes)?;
+		let count = read_u16(bytes, eocd + 10)? as usize;
+		let cd_size = read_u32(bytes, eocd + 12)? as usize;
+		let cd_offset = read_u32(bytes, eocd + 16)? as usize;
+		let comment_len = read_u16(bytes, eocd + 20)? as usize;
+		let comment = slice(bytes, eocd + 22, comment_len)?.to_vec();
+		if count == 0xFFFF || cd_offset == 0xFFFF_FFFF || cd_size == 0xFFFF_FFFF {
+			return Err(zip_error("ZIP64 archives are not supported"));
+		}
+
+		let mut entries = Vec::with_capacity(count);
+		let mut offset = cd_offset;
+		for _ in 0..count {
+			if read_u32(bytes, offset)? != CENTRAL_HEADER_SIGNATURE {
+				return Err(zip_error("bad central directory header"));
+			}
+			let name_len = read_u16(bytes, offset + 28)? as usize;
+			let extra_len = read_u16(bytes, offset + 30)? as usize;
+			let entry_comment_len = read_u16(bytes, offset + 32)? as usize;
+			let compressed_size = read_u32(bytes, offset + 20)? as usize;
+			let local_offset = read_u32(bytes, offset + 42)? as usize;
+			let name = slice(bytes, offset + 46, name_len)?;
+			let central_extra = slice(bytes, offset + 46 + name_len, extra_len)?;
+			let entry_comment = slice(bytes, offset + 46 + name_len + extra_len, entry_comment_len)?;
+
+			if read_u32(bytes, local_offset)? != LOCAL_HEADER_SIGNATURE {
+				return Err(zip_error("bad local file header"));
+			}
+			let local_name_len = read_u16(bytes, local_offset + 26)? as usize;
+			let local_extra_len = read_u16(bytes, local_offset + 28)? as usize;
+			let local_extra = slice(bytes, local_offset + 30 + local_name_len, local_extra_len)?;
+			let data_offset = local_offset + 30 + local_name_len + local_extra_len;
+			let flags = read_u16(bytes, offset + 8)?;
+			let data_end = data_offset + compressed_size;
+			let descriptor_len = match flags & FLAG_DATA_DESCRIPTOR {
+				0 => 0,
+				_ if read_u32(bytes, data_end)? == DATA_DESCRIPTOR_SIGNATURE => 16,
+				_ => 12,
+			};
+			let record = slice(
+				bytes,
+				local_offset,
+				data_end + descriptor_len - local_offset,
+			)?;
+			let method = read_u16(bytes, offset + 10)?;
+
+			entries.push(ZipEntry {
+				name: String::from_utf8_lossy(name).into_owned(),
+				raw_name: name.to_vec(),
+				version_made_by: read_u16(bytes, offset + 4)?,
+				version_needed: read_u16(bytes, offset + 6)?,
+				flags,
+				method,
+				time: read_u16(bytes, offset + 12)?,
+				date: read_u16(bytes, offset + 14)?,
+				crc32: read_u32(bytes, offset + 16)?,
+				uncompressed_size: read_u32(bytes, offset + 24)?,
+				local_extra: local_extra.to_vec(),
+				central_extra: central_extra.to_vec(),
+				comment: entry_comment.to_vec(),
+				internal_attributes: read_u16(bytes, offset + 36)?,
+				external_attributes: read_u32(bytes, offset + 38)?,
+				data: slice(bytes, data_offset, compressed_size)?.to_vec(),
+				offset: local_offset,
+				record: Some(record.to_vec()),
+				record_len: record.len(),
+				original_method: method,
+				original_data_offset: data_offset,
+				gap: Vec::new(),
+			});
+			offset += 46 + name_len + extra_len + entry_comment_len;
+		}
+
+		// Whatever lies between the local records, in the order they appear in the file, is kept.
+		let mut end = 0;
+		for index in file_order(&entries) {
+			let entry = &mut entries[index];
+			if entry.offset >= end {
+				entry.gap = bytes[end..entry.offset].to_vec();
+			}
+			end = end.max(entry.offset + entry.record_len);
+		}
+		let before_central_directory = bytes.get(end..cd_offset).unwrap_or_default().to_vec();
+		Ok(ZipArchive {
+			entries,
+			before_central_directory,
+			comment,
+		})
+	}
+
+	/// Writes the archive, followed by its central directory.
+	///
+	/// Entries whose contents were not replaced are written back byte for byte, in their
+	/// original order, along with any bytes between them and before the central directory,
+	/// such as an APK Signing Block. Up to the first replaced entry the archive is therefore
+	/// unchanged but for the central directory, and a replaced entry is padded so that the
+	/// entries after it keep their alignment (see [`ZipEntry::padding`]).
+	///
+	/// Replaced entries never have a data descriptor; their sizes and CRC live in the local header.
+	pub(crate) fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+		let mut out = Vec::new();
+		let mut offsets = vec![0u32; self.entries.len()];
+		for index in file_order(&self.entries) {
+			let entry = &self.entries[index];
+			out.extend_from_slice(&entry.gap);
+			offsets[index] = out.len() as u32;
+			match &entry.record {
+				Some(record) => out.extend_from_slice(record),
+				None => entry.write_local_record(&mut out),
+			}
+		}
+		out.extend_from_slice(&self.before_central_directory);
+
+		let cd_offset = out.len() as u32;
+		for (entry, offset) in self.entries.iter().zip(offsets) {
+			out.extend_from_slice(&CENTRAL_HEADER_SIGNATURE.to_le_bytes());
+			out.extend_from_slice(&entry.version_made_by.to_le_bytes());
+			out.extend_from_slice(&entry.version_needed.to_le_bytes());
+			out.extend_from_slice(&entry.written_flags().to_le_bytes());
+			out.extend_from_slice(&entry.method.to_le_bytes());
+			out.extend_from_slice(&entry.time.to_le_bytes());
+			out.extend_from_slice(&entry.date.to_le_bytes());
+			out.extend_from_slice(&entry.crc32.to_le_bytes());
+			out.extend_from_slice(&(entry.data.len() as u32).to_le_bytes());
+			out.extend_from_slice(&entry.uncompressed_size.to_le_bytes());
+			out.extend_from_slice(&(entry.raw_name.len() as u16).to_le_bytes());
+			out.extend_from_slice(&(entry.central_extra.len() as u16).to_le_bytes());
+			out.extend_from_slice(&(entry.comment.len() as u16).to_le_bytes());
+			out.extend_from_slice(&0u16.to_le_bytes());
+			out.extend_from_slice(&entry.internal_attributes.to_le_bytes());
+			out.extend_from_slice(&entry.external_attributes.to_le_bytes());
+			out.extend_from_slice(&offset.to_le_bytes());
+			out.extend_from_slice(&entry.raw_name);
+			out.extend_from_slice(&entry.central_extra);
+			out.extend_from_slice(&entry.comment);
+		}
+		let cd_size = out.len() as u32 - cd_offset;
+
+		out.extend_from_slice(&END_OF_CENTRAL_DIRECTORY_SIGNATURE.to_le_bytes());
+		out.extend_from_slice(&0u16.to_le_bytes());
+		out.extend_from_slice(&0u16.to_le_bytes());
+		out.extend_from_slice(&(self.entries.len() as u16).to_le_bytes());
+		out.extend_from_slice(&(self.entries.len() as u16).to_le_bytes());
+		out.extend_from_slice(&cd_size.to_le_bytes());
+		out.extend_from_slice(&cd_offset.to_le_bytes());
+		out.extend_from_slice(&(self.comment.len() as u16).to_le_bytes());
+		out.extend_from_slice(&self.comment);
+		writer.write_all(&out)
+	}
+}
+
+/// The indices of `entries` in the order of their local headers in the original archive.
+fn file_order(entries: &[ZipEntry]) -> Vec<usize> {
+	let mut order: Vec<usize> = (0..entries.len()).collect();
+	order.sort_by_key(|&index| entries[index].offset);
+	order
+}
+
+fn find_end_of_central_directory(bytes: &[u8]) -> Result<usize, DexError> {
+	// The record is 22 bytes followed by a comment of at most 65535 bytes.
+	let lowest = bytes.len().saturating_sub(22 + 0xFFFF);
+	(lowest..=bytes.len().saturating_sub(22))
+		.rev()
+		.find(|&offset| read_u32(bytes, offset).ok() == Some(END_OF_CENTRAL_DIRECTORY_SIGNATURE))
+		.ok_or_else(|| zip_error("end of central directory not found"))
+}
+
+fn slice(bytes: &[u8], offset: usize, len: usize) -> Result<&[u8], DexError> {
+	offset
+		.checked_add(len)
+		.and_then(|end| bytes.get(offset..end))
+		.ok_or_else(|| zip_error("unexpected end of archive"))
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Result<u16, DexError> {
+	Ok(u16::from_le_bytes(
+		slice(bytes, offset, 2)?.try_into().unwrap(),
+	))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32, DexError> {
+	Ok(u32::from_le_bytes(
+		slice(bytes, offset, 4)?.try_into().unwrap(),
+	))
+}
+
+fn zip_error<S: Into<String>>(message: S) -> DexError {
+	DexError::Zip(message.into())
+}
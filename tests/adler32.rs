@@ -0,0 +1,96 @@
+use std::io::Cursor;
+
+use adler32::RollingAdler32;
+use dex_checksum_tools::checksum_of;
+use dex_checksum_tools::verify_checksum_streaming;
+use dex_checksum_tools::Dex;
+use dex_checksum_tools::Endianness;
+
+/// Fills a buffer with reproducible pseudo-random bytes.
+fn pseudo_random(len: usize, seed: u64) -> Vec<u8> {
+	let mut state = seed | 1;
+	(0..len)
+		.map(|_| {
+			state ^= state << 13;
+			state ^= state >> 7;
+			state ^= state << 17;
+			state as u8
+		})
+		.collect()
+}
+
+/// The checksum the `adler32` crate computes for `bytes`, laid out like the header field.
+fn reference_checksum(bytes: &[u8]) -> [u8; 4] {
+	RollingAdler32::from_buffer(&bytes[12..])
+		.hash()
+		.to_le_bytes()
+}
+
+#[test]
+fn checksum_matches_adler32_crate() {
+	// Lengths around the lane width and the reduction interval, where the sums are folded.
+	let lens = [
+		12,
+		13,
+		15,
+		16,
+		17,
+		0x70,
+		5552,
+		5565,
+		22208,
+		22220,
+		22221,
+		22223,
+		100_003,
+		1 << 20,
+	];
+	for (seed, &len) in lens.iter().enumerate() {
+		let bytes = pseudo_random(len, seed as u64);
+		assert_eq!(
+			checksum_of(&bytes),
+			reference_checksum(&bytes),
+			"len {}",
+			len
+		);
+	}
+}
+
+#[test]
+fn checksum_matches_adler32_crate_for_saturated_bytes() {
+	// All 0xff bytes make the unreduced sums grow as fast as they can.
+	let bytes = vec![0xff; 3 << 20];
+	assert_eq!(checksum_of(&bytes), reference_checksum(&bytes));
+}
+
+#[test]
+fn streaming_and_digests_match_adler32_crate() {
+	let mut dex = Dex::empty(35).into_bytes();
+	dex.extend(pseudo_random(300_001, 7));
+	let checksum = reference_checksum(&dex);
+	dex[8..12].copy_from_slice(&checksum);
+	assert!(verify_checksum_streaming(Cursor::new(&dex)).unwrap());
+
+	let dex = Dex::try_from(dex).unwrap();
+	assert_eq!(dex.compute_digests().checksum(), dex.current_checksum());
+}
+
+#[test]
+fn reverse_endian_files_store_header_fields_big_endian() {
+	let mut bytes = Dex::empty(35).into_bytes();
+	let len = bytes.len() as u32;
+	// The tag as stored by a big-endian writer, with file_size and header_size to match.
+	bytes[40..44].copy_from_slice(&[0x12, 0x34, 0x56, 0x78]);
+	bytes[32..36].copy_from_slice(&len.to_be_bytes());
+	bytes[36..40].copy_from_slice(&0x70u32.to_be_bytes());
+	let mut dex = Dex::from_bytes(bytes);
+	assert_eq!(dex.endianness(), Endianness::Big);
+
+	assert!(dex.correct_checksum());
+	let adler = RollingAdler32::from_buffer(&dex.as_bytes()[12..]).hash();
+	assert_eq!(dex.as_bytes()[8..12], adler.to_be_bytes());
+	assert_eq!(dex.current_checksum_u32(), adler);
+	let header = dex.header().unwrap();
+	assert_eq!(header.file_size(), len);
+	assert_eq!(header.header_size(), 0x70);
+}
@@ -0,0 +1,272 @@
+use std::ops::Range;
+
+use dex_checksum_tools::apk::multidex_index;
+use dex_checksum_tools::apk::Apk;
+use dex_checksum_tools::primitives::crc32;
+use dex_checksum_tools::primitives::deflate;
+use dex_checksum_tools::Dex;
+use dex_checksum_tools::DexError;
+
+/// A hand-made APK: a manifest deflated with dynamic Huffman codes, an `assets/stored.bin`
+/// deflated into stored blocks, a stored `classes.dex` and a deflated `classes2.dex`
+/// (both built from `minimal.dex` with one byte flipped), a stored `lib/x86/libfoo.so` padded
+/// to 4096 bytes and a stored `resources.arsc` padded to 4, as by zipalign, followed by a
+/// stand-in for an APK Signing Block. Python's `zipfile` and `unzip -t` accept it.
+const SAMPLE_APK: &[u8] = include_bytes!("fixtures/sample.apk");
+
+/// The entries that are not DEX files, and are never rewritten.
+const UNTOUCHED: [&str; 4] = [
+	"AndroidManifest.xml",
+	"assets/stored.bin",
+	"lib/x86/libfoo.so",
+	"resources.arsc",
+];
+
+/// Walks the local headers of an archive without data descriptors from the start of the file,
+/// skipping any padding between them, up to the central directory.
+///
+/// # Returns
+/// The name of every entry, the range of its local header and data, and where its data starts.
+fn local_records(bytes: &[u8]) -> Vec<(String, Range<usize>, usize)> {
+	let u16_at = |offset: usize| u16::from_le_bytes([bytes[offset], bytes[offset + 1]]) as usize;
+	let next_header = |from: usize| {
+		(from..bytes.len() - 4)
+			.find(|&offset| {
+				bytes[offset..].starts_with(b"PK\x03\x04") || bytes[offset..].starts_with(b"PK\x01\x02")
+			})
+			.filter(|&offset| bytes[offset..].starts_with(b"PK\x03\x04"))
+	};
+	let mut records = Vec::new();
+	let mut offset = 0;
+	while let Some(start) = next_header(offset) {
+		offset = start;
+		let compressed_size = u16_at(offset + 18) | u16_at(offset + 20) << 16;
+		let (name_len, extra_len) = (u16_at(offset + 26), u16_at(offset + 28));
+		let name = String::from_utf8(bytes[offset + 30..offset + 30 + name_len].to_vec()).unwrap();
+		let data_start = offset + 30 + name_len + extra_len;
+		records.push((name, offset..data_start + compressed_size, data_start));
+		offset = data_start + compressed_size;
+	}
+	records
+}
+
+fn record<'a>(
+	records: &'a [(String, Range<usize>, usize)],
+	name: &str,
+) -> &'a (String, Range<usize>, usize) {
+	records.iter().find(|(entry, _, _)| entry == name).unwrap()
+}
+
+fn written(apk: &Apk) -> Vec<u8> {
+	let mut bytes = Vec::new();
+	apk.write_to(&mut bytes).unwrap();
+	bytes
+}
+
+#[test]
+fn unchanged_archive_is_written_back_byte_for_byte() {
+	assert_eq!(written(&Apk::from_bytes(SAMPLE_APK).unwrap()), SAMPLE_APK);
+}
+
+#[test]
+fn correcting_keeps_untouched_entries_alignment_and_signing_block() {
+	for store in [false, true] {
+		let mut apk = Apk::from_bytes(SAMPLE_APK).unwrap();
+		let results = if store {
+			apk.correct_all_stored()
+		} else {
+			apk.correct_all()
+		};
+		assert!(results
+			.iter()
+			.all(|result| result.changed && result.error.is_none()));
+		let output = written(&apk);
+
+		let (before, after) = (local_records(SAMPLE_APK), local_records(&output));
+		for name in UNTOUCHED {
+			let (_, original, original_data) = record(&before, name);
+			let (_, range, data) = record(&after, name);
+			assert_eq!(
+				output[range.clone()],
+				SAMPLE_APK[original.clone()],
+				"{}",
+				name
+			);
+			assert_eq!(data % 4096, original_data % 4096, "{}", name);
+		}
+		// The stored `classes.dex` keeps its length, so nothing before `classes2.dex` moves.
+		assert_eq!(
+			record(&after, "classes2.dex").1.start,
+			record(&before, "classes2.dex").1.start
+		);
+		if store {
+			assert_eq!(record(&after, "classes2.dex").2 % 4, 0);
+		}
+
+		let signing_block_end = after.last().unwrap().1.end + 48;
+		assert_eq!(
+			&output[signing_block_end - 16..signing_block_end],
+			b"APK Sig Block 42"
+		);
+
+		let corrected = Apk::from_bytes(&output).unwrap();
+		for name in ["classes.dex", "classes2.dex"] {
+			let dex = corrected.dex(name).unwrap().unwrap();
+			assert!(dex.check_checksum() && dex.check_signature(), "{}", name);
+		}
+	}
+}
+
+/// Builds an archive of stored entries, with no extra fields, comments or signing block.
+fn stored_zip(entries: &[(&str, &[u8])]) -> Vec<u8> {
+	zip_with(entries, false, &[])
+}
+
+/// Builds an archive with no comments or signing block, whose entries are all deflated or all
+/// stored, and all have `local_extra` as the extra field of their local header.
+fn zip_with(entries: &[(&str, &[u8])], deflated: bool, local_extra: &[u8]) -> Vec<u8> {
+	let (mut out, mut central_directory) = (Vec::new(), Vec::new());
+	for &(name, contents) in entries {
+		let data = match deflated {
+			true => deflate(contents),
+			false => contents.to_vec(),
+		};
+		let mut fields = vec![20, 0, 0, 0, 8 * deflated as u8, 0, 0, 0, 0, 0];
+		fields.extend_from_slice(&crc32(contents).to_le_bytes());
+		fields.extend_from_slice(&(data.len() as u32).to_le_bytes());
+		fields.extend_from_slice(&(contents.len() as u32).to_le_bytes());
+		fields.extend_from_slice(&(name.len() as u16).to_le_bytes());
+		central_directory.extend_from_slice(b"PK\x01\x02\x14\x00");
+		central_directory.extend_from_slice(&fields);
+		// The extra field and comment lengths, disk number and attributes.
+		central_directory.extend_from_slice(&[0; 12]);
+		central_directory.extend_from_slice(&(out.len() as u32).to_le_bytes());
+		central_directory.extend_from_slice(name.as_bytes());
+		out.extend_from_slice(b"PK\x03\x04");
+		out.extend_from_slice(&fields);
+		out.extend_from_slice(&(local_extra.len() as u16).to_le_bytes());
+		out.extend_from_slice(name.as_bytes());
+		out.extend_from_slice(local_extra);
+		out.extend_from_slice(&data);
+	}
+	let count = (entries.len() as u16).to_le_bytes();
+	let (size, offset) = (central_directory.len() as u32, out.len() as u32);
+	out.extend_from_slice(&central_directory);
+	out.extend_from_slice(b"PK\x05\x06\0\0\0\0");
+	out.extend_from_slice(&[count, count].concat());
+	out.extend_from_slice(&size.to_le_bytes());
+	out.extend_from_slice(&offset.to_le_bytes());
+	out.extend_from_slice(&[0, 0]);
+	out
+}
+
+#[test]
+fn only_dex_entries_android_loads_are_listed_in_loading_order() {
+	let dex = Dex::empty(35).into_bytes();
+	let names = [
+		"classes10.dex",
+		"classes1.dex",
+		"classes02.dex",
+		"classes2.dex",
+		"classes.dex",
+		"classes9.dex",
+		"assets/classes3.dex",
+	];
+	let entries: Vec<(&str, &[u8])> = names.iter().map(|&name| (name, dex.as_slice())).collect();
+	let apk = Apk::from_bytes(&stored_zip(&entries)).unwrap();
+	let listed = apk.dex_names();
+	assert_eq!(
+		listed,
+		[
+			"classes.dex",
+			"classes2.dex",
+			"classes9.dex",
+			"classes10.dex"
+		]
+	);
+	assert!(listed.iter().all(|name| multidex_index(name).is_some()));
+}
+
+#[test]
+fn an_entry_that_is_not_a_dex_file_is_refused_and_left_alone() {
+	let garbage = vec![b'x'; 300];
+	let mut stale = Dex::empty(35).into_bytes();
+	stale[0x6c] ^= 0xff;
+	let bytes = stored_zip(&[("classes.dex", &garbage), ("classes2.dex", &stale)]);
+	let mut apk = Apk::from_bytes(&bytes).unwrap();
+
+	let results = apk.correct_all();
+	assert!(matches!(results[0].error, Some(DexError::BadMagic(_))));
+	assert!(!results[0].changed);
+	assert!(results[1].error.is_none() && results[1].changed);
+	assert_eq!(
+		apk.dex("classes.dex").unwrap().unwrap().into_bytes(),
+		garbage
+	);
+}
+
+#[test]
+fn rewritten_records_keep_a_single_alignment_field() {
+	let mut stale = Dex::empty(35).into_bytes();
+	stale[0x6c] ^= 0xff;
+	// A zipalign field, then another field that has to survive the rewrite.
+	let local_extra = [0x35, 0xd9, 2, 0, 4, 0, 0xfe, 0xca, 1, 0, 7];
+	for deflated in [false, true] {
+		let bytes = zip_with(&[("classes.dex", &stale)], deflated, &local_extra);
+		let mut apk = Apk::from_bytes(&bytes).unwrap();
+		assert!(apk.correct_all()[0].changed);
+		let output = written(&apk);
+		let records = local_records(&output);
+		let (_, range, data_start) = record(&records, "classes.dex");
+		let mut extra = &output[range.start + 30 + "classes.dex".len()..*data_start];
+		let mut ids = Vec::new();
+		while extra.len() >= 4 {
+			ids.push(u16::from_le_bytes([extra[0], extra[1]]));
+			extra = &extra[4 + u16::from_le_bytes([extra[2], extra[3]]) as usize..];
+		}
+		assert!(extra.is_empty());
+		assert_eq!(
+			ids.iter().filter(|&&id| id == 0xd935).count(),
+			1,
+			"{:x?}",
+			ids
+		);
+		assert!(ids.contains(&0xcafe), "{:x?}", ids);
+		assert!(Apk::from_bytes(&output)
+			.unwrap()
+			.dex("classes.dex")
+			.unwrap()
+			.unwrap()
+			.check_checksum());
+	}
+}
+
+#[test]
+fn inflating_stops_at_the_declared_size() {
+	let mut bytes = SAMPLE_APK.to_vec();
+	let (_, range, _) = record(&local_records(SAMPLE_APK), "classes2.dex").clone();
+	// Halve the uncompressed size of the deflated classes2.dex, in its local header and in the
+	// central directory.
+	let size = u32::from_le_bytes(
+		bytes[range.start + 22..range.start + 26]
+			.try_into()
+			.unwrap(),
+	);
+	let central = bytes
+		.windows(4)
+		.enumerate()
+		.filter(|(_, window)| window == b"PK\x01\x02")
+		.map(|(offset, _)| offset)
+		.find(|&offset| bytes[offset + 46..].starts_with(b"classes2.dex"))
+		.unwrap();
+	bytes[range.start + 22..range.start + 26].copy_from_slice(&(size / 2).to_le_bytes());
+	bytes[central + 24..central + 28].copy_from_slice(&(size / 2).to_le_bytes());
+
+	let apk = Apk::from_bytes(&bytes).unwrap();
+	let error = apk.dex("classes2.dex").unwrap_err();
+	assert!(
+		error.to_string().contains("longer than expected"),
+		"{}",
+		error
+	);
+}
@@ -0,0 +1,105 @@
+//! Reading a `Dex` through the runtime-agnostic `AsyncRead`, driven by a minimal executor.
+#![cfg(feature = "async")]
+
+use std::future::Future;
+use std::io;
+use std::pin::pin;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::Context;
+use std::task::Poll;
+use std::task::Wake;
+use std::thread;
+use std::thread::Thread;
+
+use dex_checksum_tools::async_read::AsyncRead;
+use dex_checksum_tools::Dex;
+
+struct Unpark(Thread);
+
+impl Wake for Unpark {
+	fn wake(self: Arc<Self>) {
+		self.0.unpark();
+	}
+}
+
+/// Polls `future` on the current thread until it completes.
+fn block_on<F: Future>(future: F) -> F::Output {
+	let waker = Arc::new(Unpark(thread::current())).into();
+	let mut cx = Context::from_waker(&waker);
+	let mut future = pin!(future);
+	loop {
+		match future.as_mut().poll(&mut cx) {
+			Poll::Ready(output) => return output,
+			Poll::Pending => thread::park(),
+		}
+	}
+}
+
+/// Hands out a few bytes at a time, and is only ready on every other poll, like a socket.
+struct Trickle {
+	bytes: Vec<u8>,
+	offset: usize,
+	ready: bool,
+	interrupted: bool,
+}
+
+impl AsyncRead for Trickle {
+	fn poll_read(
+		mut self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+		buf: &mut [u8],
+	) -> Poll<io::Result<usize>> {
+		self.ready = !self.ready;
+		if !self.ready {
+			cx.waker().wake_by_ref();
+			return Poll::Pending;
+		}
+		if !self.interrupted {
+			self.interrupted = true;
+			return Poll::Ready(Err(io::ErrorKind::Interrupted.into()));
+		}
+		let len = buf.len().min(7).min(self.bytes.len() - self.offset);
+		buf[..len].copy_from_slice(&self.bytes[self.offset..self.offset + len]);
+		self.offset += len;
+		Poll::Ready(Ok(len))
+	}
+}
+
+#[test]
+fn reads_a_slice() {
+	let bytes = Dex::empty(35).into_bytes();
+	let dex = block_on(Dex::from_async_reader(bytes.as_slice())).unwrap();
+	assert_eq!(dex, Dex::empty(35));
+}
+
+#[test]
+fn waits_for_a_reader_that_is_not_always_ready() {
+	let mut reader = Trickle {
+		bytes: Dex::empty(35).into_bytes(),
+		offset: 0,
+		ready: false,
+		interrupted: false,
+	};
+	let dex = block_on(Dex::from_async_reader(&mut reader)).unwrap();
+	assert!(dex.check_checksum() && dex.check_signature());
+	assert_eq!(reader.offset, dex.len());
+}
+
+#[test]
+fn returns_the_first_read_error() {
+	struct Failing;
+
+	impl AsyncRead for Failing {
+		fn poll_read(
+			self: Pin<&mut Self>,
+			_cx: &mut Context<'_>,
+			_buf: &mut [u8],
+		) -> Poll<io::Result<usize>> {
+			Poll::Ready(Err(io::ErrorKind::BrokenPipe.into()))
+		}
+	}
+
+	let error = block_on(Dex::from_async_reader(Failing)).unwrap_err();
+	assert_eq!(error.kind(), io::ErrorKind::BrokenPipe);
+}
@@ -0,0 +1,63 @@
+//! RFC 4648 test vectors for the base64 decoding behind `Dex::from_base64`, and its checks.
+#![cfg(feature = "base64")]
+
+use dex_checksum_tools::primitives::base64::decode;
+use dex_checksum_tools::Dex;
+use dex_checksum_tools::DexError;
+
+#[test]
+fn rfc_4648_test_vectors() {
+	let vectors = [
+		("", ""),
+		("Zg==", "f"),
+		("Zm8=", "fo"),
+		("Zm9v", "foo"),
+		("Zm9vYg==", "foob"),
+		("Zm9vYmE=", "fooba"),
+		("Zm9vYmFy", "foobar"),
+	];
+	for (encoded, decoded) in vectors {
+		assert_eq!(
+			decode(encoded),
+			Some(decoded.as_bytes().to_vec()),
+			"{:?}",
+			encoded
+		);
+	}
+}
+
+#[test]
+fn padding_is_optional_and_whitespace_is_ignored() {
+	assert_eq!(decode("Zg"), Some(b"f".to_vec()));
+	assert_eq!(decode("Zm9v\nYmE"), Some(b"fooba".to_vec()));
+	assert_eq!(decode(" Zm9v YmFy \r\n"), Some(b"foobar".to_vec()));
+}
+
+#[test]
+fn rejects_invalid_text() {
+	assert_eq!(decode("Z"), None);
+	assert_eq!(decode("Zm9v!"), None);
+	assert_eq!(decode("Zg=a"), None);
+	assert_eq!(decode("Zg=="), Some(b"f".to_vec()));
+	assert_eq!(decode("Zg==="), None);
+	// URL-safe digits are not part of the standard alphabet.
+	assert_eq!(decode("-_8="), None);
+}
+
+#[test]
+fn from_base64_checks_the_decoded_bytes() {
+	// 0x70 zero bytes.
+	let zeros = "A".repeat(0x70 / 3 * 4) + "AA==";
+	assert!(matches!(
+		Dex::from_base64(&zeros),
+		Err(DexError::BadMagic(_))
+	));
+	assert!(matches!(
+		Dex::from_base64("ZGV4CjAzNQA="),
+		Err(DexError::TooShort { .. })
+	));
+	assert!(matches!(
+		Dex::from_base64("not base64!"),
+		Err(DexError::Decode(_))
+	));
+}
@@ -0,0 +1,101 @@
+#![cfg(feature = "std-fs")]
+
+use std::fs;
+use std::io;
+
+use dex_checksum_tools::batch::backup_file;
+use dex_checksum_tools::batch::correct_files_with;
+use dex_checksum_tools::batch::BatchOptions;
+use dex_checksum_tools::batch::FileError;
+use dex_checksum_tools::Dex;
+
+#[test]
+fn reports_every_file_to_the_callback_and_corrects_in_place() {
+	let dir = std::env::temp_dir().join(format!("dex_checksum_tools_batch_{}", std::process::id()));
+	fs::create_dir_all(&dir).unwrap();
+	let mut bytes = Dex::empty(35).into_bytes();
+	bytes[8] ^= 0xff;
+	let paths = [
+		dir.join("classes.dex"),
+		dir.join("classes2.dex"),
+		dir.join("backup.dex"),
+	];
+	fs::write(&paths[0], &bytes).unwrap();
+	fs::write(&paths[1], Dex::empty(35).as_bytes()).unwrap();
+	fs::write(&paths[2], b"not a dex file").unwrap();
+
+	let options = BatchOptions {
+		jobs: 2,
+		..BatchOptions::default()
+	};
+	let mut reported = Vec::new();
+	let results = correct_files_with(&paths, &options, |path, result| {
+		reported.push((path.to_string(), result.changed));
+	});
+
+	assert_eq!(reported.len(), 3);
+	assert!(reported.contains(&(paths[0].to_string_lossy().into_owned(), true)));
+	assert!(results[0].changed && results[0].error.is_none());
+	assert!(!results[1].changed && results[1].output.is_none());
+	assert!(matches!(results[2].error, Some(FileError::NotADex(_))));
+	assert_eq!(fs::read(&paths[0]).unwrap(), Dex::empty(35).into_bytes());
+	assert_eq!(fs::read(&paths[2]).unwrap(), b"not a dex file");
+	fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn backups_never_overwrite_an_existing_backup() {
+	let dir = std::env::temp_dir().join(format!("dex_checksum_tools_backup_{}", std::process::id()));
+	fs::create_dir_all(&dir).unwrap();
+	let path = dir.join("classes.dex");
+	fs::write(&path, b"first").unwrap();
+
+	assert_eq!(
+		backup_file(&path, ".bak", false).unwrap(),
+		dir.join("classes.dex.bak")
+	);
+	fs::write(&path, b"second").unwrap();
+	let error = backup_file(&path, ".bak", false).unwrap_err();
+	assert_eq!(error.kind(), io::ErrorKind::AlreadyExists);
+	assert_eq!(
+		backup_file(&path, ".bak", true).unwrap(),
+		dir.join("classes.dex.bak.1")
+	);
+
+	assert_eq!(fs::read(dir.join("classes.dex.bak")).unwrap(), b"first");
+	assert_eq!(fs::read(dir.join("classes.dex.bak.1")).unwrap(), b"second");
+	fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn corrects_stale_signatures_like_a_single_correction() {
+	let dir = std::env::temp_dir().join(format!(
+		"dex_checksum_tools_signature_{}",
+		std::process::id()
+	));
+	fs::create_dir_all(&dir).unwrap();
+	// Patching a body byte leaves both the signature and the checksum stale.
+	let mut bytes = Dex::empty(35).into_bytes();
+	let last = bytes.len() - 1;
+	bytes[last] ^= 0x01;
+	let mut expected = Dex::from_bytes(bytes.clone());
+	expected.correct_all();
+	let paths = [dir.join("classes.dex")];
+	fs::write(&paths[0], &bytes).unwrap();
+
+	let options = BatchOptions {
+		signature: true,
+		..BatchOptions::default()
+	};
+	let results = correct_files_with(&paths, &options, |_, _| {});
+
+	let result = &results[0];
+	assert!(result.changed && result.error.is_none());
+	assert_ne!(result.old_signature, result.new_signature);
+	assert_eq!(result.new_signature, Some(expected.current_signature()));
+	assert_eq!(result.new_checksum, Some(expected.current_checksum_u32()));
+	let corrected = Dex::from_bytes(fs::read(&paths[0]).unwrap());
+	assert!(corrected.check_signature() && corrected.check_checksum());
+	assert_eq!(corrected.as_bytes(), expected.as_bytes());
+	fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,137 @@
+use std::fs;
+use std::process::Command;
+
+use dex_checksum_tools::Dex;
+
+fn dex_checksum_tools() -> Command {
+	Command::new(env!("CARGO_BIN_EXE_dex_checksum_tools"))
+}
+
+#[test]
+fn command_line_short_flags_override_default_options() {
+	let dir = std::env::temp_dir().join(format!("dex_checksum_tools_cli_{}", std::process::id()));
+	fs::create_dir_all(&dir).unwrap();
+	let path = dir.join("classes.dex");
+	let mut bytes = Dex::empty(35).into_bytes();
+	let last = bytes.len() - 1;
+	bytes[last] ^= 0x01;
+	fs::write(&path, &bytes).unwrap();
+
+	let output = dex_checksum_tools()
+		.env("DEX_CHECKSUM_TOOLS_OPTS", "--jobs=4 --signature --backup")
+		.args(["correct-all", "-j", "2"])
+		.arg(&path)
+		.output()
+		.unwrap();
+
+	assert!(
+		output.status.success(),
+		"{}",
+		String::from_utf8_lossy(&output.stderr)
+	);
+	// `--signature` was still added, and `--backup`, which correct-all does not accept, ignored.
+	let corrected = Dex::from_bytes(fs::read(&path).unwrap());
+	assert!(corrected.check_signature() && corrected.check_checksum());
+	assert!(!dir.join("classes.dex.bak").exists());
+	fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn verify_apk_reports_an_unreadable_entry_and_audits_the_rest() {
+	let dir = std::env::temp_dir().join(format!("dex_checksum_tools_verify_{}", std::process::id()));
+	fs::create_dir_all(&dir).unwrap();
+	let path = dir.join("corrupt.apk");
+	let mut bytes = include_bytes!("fixtures/sample.apk").to_vec();
+	// Flip a byte inside the stored classes.dex, so that its CRC-32 no longer matches.
+	let name = bytes
+		.windows(11)
+		.position(|window| window == b"classes.dex")
+		.unwrap();
+	let extra_len = u16::from_le_bytes([bytes[name - 2], bytes[name - 1]]) as usize;
+	bytes[name + 11 + extra_len + 0x20] ^= 0x01;
+	fs::write(&path, &bytes).unwrap();
+
+	let output = dex_checksum_tools()
+		.args(["--json", "verify-apk"])
+		.arg(&path)
+		.output()
+		.unwrap();
+
+	assert_eq!(output.status.code(), Some(1));
+	let stdout = String::from_utf8(output.stdout).unwrap();
+	let lines: Vec<&str> = stdout.lines().collect();
+	assert_eq!(lines.len(), 2, "{}", stdout);
+	assert!(lines[0].contains("\"name\":\"classes.dex\"") && lines[0].contains("\"error\":"));
+	assert!(
+		lines[1].contains("\"name\":\"classes2.dex\"") && lines[1].contains("\"checksum_valid\"")
+	);
+	fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn correct_checksum_json_reports_the_checksum_written_after_the_signature() {
+	let dir = std::env::temp_dir().join(format!("dex_checksum_tools_json_{}", std::process::id()));
+	fs::create_dir_all(&dir).unwrap();
+	let path = dir.join("classes.dex");
+	let mut bytes = Dex::empty(35).into_bytes();
+	let last = bytes.len() - 1;
+	bytes[last] ^= 0x01;
+	fs::write(&path, &bytes).unwrap();
+	let stale = Dex::from_bytes(bytes).current_checksum_u32();
+
+	let output = dex_checksum_tools()
+		.args(["--json", "correct-checksum", "--signature"])
+		.arg(&path)
+		.output()
+		.unwrap();
+
+	assert!(output.status.success());
+	let corrected = Dex::from_bytes(fs::read(&path).unwrap());
+	assert!(corrected.check_signature() && corrected.check_checksum());
+	let stdout = String::from_utf8(output.stdout).unwrap();
+	let field = |name: &str, value: u32| format!("\"{}\":\"{:#010x}\"", name, value);
+	assert!(stdout.contains(&field("current", stale)), "{}", stdout);
+	assert!(
+		stdout.contains(&field("expected", corrected.current_checksum_u32())),
+		"{}",
+		stdout
+	);
+	assert!(stdout.contains("\"valid\":false"), "{}", stdout);
+	fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn backup_copies_an_existing_output_file_and_is_refused_for_stdout() {
+	let dir = std::env::temp_dir().join(format!("dex_checksum_tools_backup_{}", std::process::id()));
+	fs::create_dir_all(&dir).unwrap();
+	let (input, output) = (dir.join("in.dex"), dir.join("out.dex"));
+	let mut bytes = Dex::empty(35).into_bytes();
+	bytes[8] ^= 0xff;
+	fs::write(&input, &bytes).unwrap();
+	fs::write(&output, b"previous output").unwrap();
+
+	let status = dex_checksum_tools()
+		.args(["correct-checksum", "--backup"])
+		.arg(&input)
+		.arg(&output)
+		.status()
+		.unwrap();
+	assert!(status.success());
+	assert_eq!(
+		fs::read(dir.join("out.dex.bak")).unwrap(),
+		b"previous output"
+	);
+	assert_eq!(fs::read(&output).unwrap(), Dex::empty(35).into_bytes());
+	assert_eq!(fs::read(&input).unwrap(), bytes);
+
+	let output = dex_checksum_tools()
+		.args(["correct-checksum", "--backup"])
+		.arg(&input)
+		.arg("-")
+		.output()
+		.unwrap();
+	assert!(!output.status.success());
+	assert!(output.stdout.is_empty());
+	assert!(String::from_utf8_lossy(&output.stderr).contains("--backup"));
+	fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,151 @@
+use dex_checksum_tools::correct_checksum_in_place;
+use dex_checksum_tools::correct_dex_bytes;
+use dex_checksum_tools::correct_signature_in_place;
+use dex_checksum_tools::Dex;
+use dex_checksum_tools::DexError;
+
+/// A minimal DEX file: a header with empty id tables and a `map_list`. Its checksum
+/// (0xd9700bbe) and signature were checked against zlib and `sha1sum`.
+const MINIMAL_DEX: &[u8] = include_bytes!("fixtures/minimal.dex");
+
+#[test]
+fn pristine_fixture_has_correct_checksum() {
+	let dex = Dex::from_slice(MINIMAL_DEX);
+	assert_eq!(dex.current_checksum_u32(), 0xd9700bbe);
+	assert_eq!(dex.expect_checksum(), dex.current_checksum());
+	assert!(dex.check_checksum());
+	assert!(dex.check_signature());
+}
+
+#[test]
+fn corrupting_a_body_byte_breaks_the_checksum_until_corrected() {
+	let mut bytes = MINIMAL_DEX.to_vec();
+	let last = bytes.len() - 1;
+	bytes[last] ^= 0x01;
+	let mut dex = Dex::from_bytes(bytes);
+	assert!(!dex.check_checksum());
+
+	assert!(dex.correct_checksum());
+	assert!(dex.check_checksum());
+	assert_ne!(dex.current_checksum_u32(), 0xd9700bbe);
+	assert!(!dex.correct_checksum());
+}
+
+#[test]
+fn empty_builder_matches_fixture() {
+	assert_eq!(Dex::empty(35).as_bytes(), MINIMAL_DEX);
+}
+
+#[test]
+fn shuffled_map_list_is_inconsistent_even_when_signed() {
+	assert_eq!(
+		Dex::from_slice(MINIMAL_DEX).check_map_consistency().ok(),
+		Some(true)
+	);
+
+	let mut bytes = MINIMAL_DEX.to_vec();
+	let (header_item, map_item) = bytes[0x74..0x8c].split_at_mut(12);
+	header_item.swap_with_slice(map_item);
+	let mut dex = Dex::from_bytes(bytes);
+	dex.correct_all();
+	assert!(dex.check_checksum() && dex.check_signature());
+	assert_eq!(dex.check_map_consistency().ok(), Some(false));
+}
+
+#[test]
+fn correct_dex_bytes_restores_a_corrupted_header() {
+	let mut bytes = MINIMAL_DEX.to_vec();
+	bytes[8..32].fill(0);
+	assert_eq!(correct_dex_bytes(bytes).unwrap(), MINIMAL_DEX);
+	assert!(correct_dex_bytes(MINIMAL_DEX[..0x40].to_vec()).is_err());
+	let mut not_a_dex = MINIMAL_DEX.to_vec();
+	not_a_dex[0..4].copy_from_slice(b"\x7fELF");
+	assert!(matches!(
+		correct_dex_bytes(not_a_dex),
+		Err(DexError::BadMagic(_))
+	));
+}
+
+#[test]
+fn in_place_corrections_refuse_bytes_that_are_not_a_dex_file() {
+	let mut bytes = MINIMAL_DEX.to_vec();
+	bytes[0..4].copy_from_slice(b"\x7fELF");
+	let original = bytes.clone();
+	assert!(matches!(
+		correct_checksum_in_place(&mut bytes),
+		Err(DexError::BadMagic(_))
+	));
+	assert!(matches!(
+		correct_signature_in_place(&mut bytes),
+		Err(DexError::BadMagic(_))
+	));
+	assert_eq!(bytes, original);
+}
+
+#[test]
+fn dex_containers_are_refused_rather_than_mangled() {
+	let mut bytes = MINIMAL_DEX.to_vec();
+	bytes[4..7].copy_from_slice(b"041");
+	let mut dex = Dex::from_bytes(bytes.clone());
+	assert!(dex.is_container_dex());
+	assert!(!Dex::from_slice(MINIMAL_DEX).is_container_dex());
+
+	assert!(matches!(
+		dex.try_correct_all(),
+		Err(DexError::UnsupportedFormat(_))
+	));
+	assert_eq!(dex.as_bytes(), &bytes[..]);
+	assert!(correct_checksum_in_place(&mut bytes).is_err());
+	assert_eq!(dex.as_bytes(), &bytes[..]);
+}
+
+#[test]
+#[cfg(feature = "std-fs")]
+fn open_reads_the_fixture_from_its_path() {
+	let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/minimal.dex");
+	let dex = Dex::open(path).unwrap();
+	assert_eq!(dex.as_bytes(), MINIMAL_DEX);
+	assert_eq!(dex.source_path(), Some(std::path::Path::new(path)));
+	assert!(Dex::open(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures")).is_err());
+}
+
+/// Builds a DEX file whose `map_list` lists the header, `sections` one-byte sections of
+/// distinct, unknown type codes and the `map_list` itself.
+fn dex_with_map_list(sections: u16) -> Vec<u8> {
+	let map_off = 0x70 + sections as u32;
+	let mut items = vec![[0x0000, 1, 0]];
+	items.extend((0..sections as u32).map(|i| [0x2000 + i, 1, 0x70 + i]));
+	items.push([0x1000, 1, map_off]);
+	let map_end = map_off + 4 + 12 * items.len() as u32;
+
+	let mut bytes = MINIMAL_DEX[..0x70].to_vec();
+	bytes[0x20..0x24].copy_from_slice(&map_end.to_le_bytes());
+	bytes[0x34..0x38].copy_from_slice(&map_off.to_le_bytes());
+	bytes[0x68..0x6c].copy_from_slice(&(map_end - 0x70).to_le_bytes());
+	bytes[0x6c..0x70].copy_from_slice(&0x70u32.to_le_bytes());
+	bytes.resize(map_off as usize, 0);
+	bytes.extend_from_slice(&(items.len() as u32).to_le_bytes());
+	for [type_code, size, offset] in items {
+		bytes.extend_from_slice(&(type_code as u16).to_le_bytes());
+		bytes.extend_from_slice(&[0, 0]);
+		bytes.extend_from_slice(&size.to_le_bytes());
+		bytes.extend_from_slice(&offset.to_le_bytes());
+	}
+	bytes
+}
+
+#[test]
+fn duplicate_map_list_types_are_found_in_long_lists() {
+	let mut bytes = dex_with_map_list(50_000);
+	assert_eq!(
+		Dex::from_slice(&bytes).check_map_consistency().ok(),
+		Some(true)
+	);
+	// The type code of the second to last section, repeated by the last one.
+	let last_section = bytes.len() - 2 * 12;
+	bytes.copy_within(last_section - 12..last_section - 10, last_section);
+	assert_eq!(
+		Dex::from_slice(&bytes).check_map_consistency().ok(),
+		Some(false)
+	);
+}
@@ -0,0 +1,52 @@
+//! Round trips for the gzip support behind `.dex.gz` files.
+#![cfg(feature = "gzip")]
+
+use dex_checksum_tools::primitives::gzip::compress;
+use dex_checksum_tools::primitives::gzip::decompress;
+use dex_checksum_tools::primitives::gzip::is_gzip;
+
+/// `b"Hello, gzip!\n"` three times, as compressed by Python's `gzip.compress(..., mtime=0)`.
+const PYTHON_GZIP: [u8; 36] = [
+	0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0xf3, 0x48, 0xcd, 0xc9, 0xc9, 0xd7,
+	0x51, 0x48, 0xaf, 0xca, 0x2c, 0x50, 0xe4, 0xf2, 0xc0, 0xc9, 0x01, 0x00, 0x21, 0xaa, 0x79, 0x15,
+	0x27, 0x00, 0x00, 0x00,
+];
+
+#[test]
+fn decompresses_a_file_written_by_another_implementation() {
+	assert!(is_gzip(&PYTHON_GZIP));
+	assert_eq!(
+		decompress(&PYTHON_GZIP).unwrap(),
+		b"Hello, gzip!\n".repeat(3)
+	);
+}
+
+#[test]
+fn decompress_undoes_compress() {
+	let inputs = [
+		Vec::new(),
+		b"dex\n035\0".to_vec(),
+		b"Hello, gzip!\n".repeat(1000),
+		(0..=255).collect::<Vec<u8>>().repeat(300),
+	];
+	for input in inputs {
+		let compressed = compress(&input);
+		assert!(is_gzip(&compressed));
+		assert_eq!(
+			decompress(&compressed).unwrap(),
+			input,
+			"length {}",
+			input.len()
+		);
+	}
+}
+
+#[test]
+fn rejects_corrupt_files() {
+	let mut corrupt = PYTHON_GZIP;
+	// The stored CRC-32 no longer matches the data.
+	corrupt[28] ^= 1;
+	assert!(decompress(&corrupt).is_err());
+	assert!(decompress(&PYTHON_GZIP[..20]).is_err());
+	assert!(!is_gzip(b"dex\n035\0"));
+}
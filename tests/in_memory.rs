@@ -0,0 +1,115 @@
+use std::io::Cursor;
+
+use dex_checksum_tools::Dex;
+use dex_checksum_tools::DexError;
+
+#[test]
+fn corrects_checksum_of_dex_read_from_cursor() {
+	let mut bytes = Dex::empty(35).into_bytes();
+	bytes[8] ^= 0xff;
+	let mut dex = Dex::from_reader(Cursor::new(bytes)).unwrap();
+	assert!(!dex.check_checksum());
+
+	assert!(dex.correct_checksum());
+	assert!(dex.check_checksum());
+	assert_eq!(dex, Dex::empty(35));
+}
+
+#[test]
+fn rejects_zip_archive_passed_as_dex() {
+	let mut bytes = Dex::empty(35).into_bytes();
+	bytes[0..4].copy_from_slice(b"PK\x03\x04");
+	assert!(matches!(
+		Dex::try_from(bytes.clone()),
+		Err(DexError::NotADex { looks_like: "zip" })
+	));
+	assert!(matches!(
+		Dex::from_bytes(bytes).try_correct_all(),
+		Err(DexError::NotADex { looks_like: "zip" })
+	));
+}
+
+#[test]
+fn try_expect_checksum_only_fails_on_short_files() {
+	let dex = Dex::empty(35);
+	assert_eq!(dex.try_expect_checksum().unwrap(), dex.expect_checksum());
+	let truncated = Dex::from_bytes(dex.into_bytes()[..12].to_vec());
+	assert!(matches!(
+		truncated.try_expect_checksum(),
+		Err(DexError::TooShort {
+			needed: 0x70,
+			got: 12
+		})
+	));
+}
+
+#[test]
+fn try_from_bytes_checks_the_magic() {
+	let mut bytes = Dex::empty(35).into_bytes();
+	assert!(Dex::try_from(bytes.as_slice()).is_ok());
+	bytes[0..4].copy_from_slice(b"cdex");
+	assert!(matches!(
+		Dex::try_from(bytes.as_slice()),
+		Err(DexError::UnsupportedFormat(_))
+	));
+	bytes[0..8].copy_from_slice(b"\x7fELF\x02\x01\x01\0");
+	assert!(matches!(
+		Dex::try_from(bytes.clone()),
+		Err(DexError::BadMagic(_))
+	));
+	assert!(matches!(
+		Dex::try_from(&bytes[..8]),
+		Err(DexError::BadMagic(_))
+	));
+	assert!(matches!(
+		Dex::try_from(&Dex::empty(35).into_bytes()[..16]),
+		Err(DexError::TooShort {
+			needed: 0x70,
+			got: 16
+		})
+	));
+}
+
+#[test]
+fn try_correct_all_refuses_bytes_without_a_dex_magic() {
+	let bytes: Vec<u8> = (0..200u32).map(|i| (i * 37 + 11) as u8).collect();
+	let mut dex = Dex::from_bytes(bytes.clone());
+	assert!(matches!(dex.try_correct_all(), Err(DexError::BadMagic(_))));
+	assert!(matches!(
+		dex.try_correct_checksum(),
+		Err(DexError::BadMagic(_))
+	));
+	assert!(matches!(
+		dex.try_correct_signature(),
+		Err(DexError::BadMagic(_))
+	));
+	assert_eq!(dex.into_bytes(), bytes);
+}
+
+#[test]
+fn from_hexdump_checks_the_decoded_bytes() {
+	let hex = |bytes: &[u8]| {
+		bytes
+			.iter()
+			.map(|b| format!("{:02x}", b))
+			.collect::<String>()
+	};
+	assert!(matches!(
+		Dex::from_hexdump(&hex(&[0; 0x70])),
+		Err(DexError::BadMagic(_))
+	));
+	let mut compact = b"cdex001\0".to_vec();
+	compact.resize(0x70, 0);
+	assert!(matches!(
+		Dex::from_hexdump(&hex(&compact)),
+		Err(DexError::UnsupportedFormat(_))
+	));
+	assert!(matches!(
+		Dex::from_hexdump(&hex(&Dex::empty(35).into_bytes()[..0x40])),
+		Err(DexError::TooShort { needed: 0x70, .. })
+	));
+	assert_eq!(
+		Dex::from_hexdump(&hex(&Dex::empty(35).into_bytes())).unwrap(),
+		Dex::empty(35)
+	);
+}
@@ -0,0 +1,170 @@
+//! Round-trip properties of checksum correction, checked over many random buffers.
+//!
+//! The buffers come from a fixed-seed generator, so a failure is reproducible from the
+//! reported case number.
+
+use dex_checksum_tools::Dex;
+use dex_checksum_tools::DexError;
+use dex_checksum_tools::DEX_LAYOUT;
+
+/// The number of random buffers each property is checked against.
+const CASES: u64 = 256;
+
+/// A xorshift64 generator, enough to spread buffers over lengths, contents and endianness.
+struct Rng(u64);
+
+impl Rng {
+	fn new(case: u64) -> Rng {
+		Rng(case.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1)
+	}
+
+	fn next(&mut self) -> u64 {
+		self.0 ^= self.0 << 13;
+		self.0 ^= self.0 >> 7;
+		self.0 ^= self.0 << 17;
+		self.0
+	}
+
+	/// Generates a buffer of at least the header length.
+	fn buffer(&mut self) -> Vec<u8> {
+		let len = 0x70 + (self.next() % 4096) as usize;
+		let mut bytes: Vec<u8> = (0..len).map(|_| self.next() as u8).collect();
+		// Half of the buffers use the reverse-endian tag, which changes the checksum layout.
+		if self.next() & 1 == 0 {
+			bytes[40..44].copy_from_slice(&[0x12, 0x34, 0x56, 0x78]);
+		}
+		bytes
+	}
+}
+
+#[test]
+fn corrected_checksum_is_valid() {
+	for case in 0..CASES {
+		let mut dex = Dex::from_bytes(Rng::new(case).buffer());
+		dex.correct_checksum();
+		assert!(dex.check_checksum(), "case {}", case);
+	}
+}
+
+#[test]
+fn correcting_twice_is_a_no_op() {
+	for case in 0..CASES {
+		let mut dex = Dex::from_bytes(Rng::new(case).buffer());
+		dex.correct_checksum();
+		let corrected = dex.clone();
+		assert!(!dex.correct_checksum(), "case {}", case);
+		assert_eq!(dex, corrected, "case {}", case);
+	}
+}
+
+#[test]
+fn correction_only_touches_the_checksum() {
+	for case in 0..CASES {
+		let bytes = Rng::new(case).buffer();
+		let mut dex = Dex::from_bytes(bytes.clone());
+		dex.correct_checksum();
+		let corrected = dex.as_bytes();
+		assert_eq!(corrected[..8], bytes[..8], "case {}", case);
+		assert_eq!(corrected[12..], bytes[12..], "case {}", case);
+	}
+}
+
+#[test]
+fn correct_all_yields_valid_signature_and_checksum() {
+	for case in 0..CASES {
+		let mut dex = Dex::from_bytes(Rng::new(case).buffer());
+		dex.correct_all();
+		assert!(
+			dex.check_signature() && dex.check_checksum(),
+			"case {}",
+			case
+		);
+		assert!(!dex.correct_all(), "case {}", case);
+	}
+}
+
+#[test]
+fn dex_layout_matches_standard_correction() {
+	for case in 0..CASES {
+		let bytes = Rng::new(case).buffer();
+		let mut standard = Dex::from_bytes(bytes.clone());
+		let mut with_layout = Dex::from_bytes(bytes);
+		let changed = with_layout
+			.correct_checksum_with_layout(&DEX_LAYOUT)
+			.unwrap();
+		assert_eq!(changed, standard.correct_checksum(), "case {}", case);
+		assert_eq!(with_layout, standard, "case {}", case);
+	}
+}
+
+/// Checks that an incremental update after replacing `offset..offset + new.len()` with `new`
+/// yields the same file as patching the bytes and correcting the checksum from scratch.
+fn assert_incremental_matches_full(bytes: &[u8], offset: usize, new: &[u8], case: u64) {
+	let mut incremental = Dex::from_bytes(bytes.to_vec());
+	incremental.correct_checksum();
+	let mut full = incremental.clone();
+	let old = incremental.as_bytes()[offset..offset + new.len()].to_vec();
+	incremental
+		.recompute_checksum_after_edit(offset, &old, new)
+		.unwrap();
+	full.patch(offset, new).unwrap();
+	full.correct_checksum();
+	assert_eq!(incremental, full, "case {} at {:#x}", case, offset);
+}
+
+#[test]
+fn incremental_checksum_matches_full_correction() {
+	for case in 0..CASES {
+		let mut rng = Rng::new(case);
+		let bytes = rng.buffer();
+		let len = 1 + (rng.next() % 64) as usize;
+		let new: Vec<u8> = (0..len).map(|_| rng.next() as u8).collect();
+		let anywhere = 12 + (rng.next() as usize) % (bytes.len() - 12 - len);
+		// Anywhere in the data, at its very start, and ending at its very last byte.
+		for offset in [anywhere, 12, bytes.len() - len] {
+			assert_incremental_matches_full(&bytes, offset, &new, case);
+		}
+	}
+}
+
+#[test]
+fn incremental_checksum_follows_a_flipped_endian_tag() {
+	for case in 0..CASES {
+		let bytes = Rng::new(case).buffer();
+		let tag = if bytes[40..44] == [0x12, 0x34, 0x56, 0x78] {
+			[0x78, 0x56, 0x34, 0x12]
+		} else {
+			[0x12, 0x34, 0x56, 0x78]
+		};
+		assert_incremental_matches_full(&bytes, 40, &tag, case);
+	}
+}
+
+#[test]
+fn incremental_checksum_rejects_bad_edits() {
+	let mut dex = Dex::empty(35);
+	let original = dex.clone();
+	let len = dex.len();
+	let at = |dex: &Dex, offset: usize| dex.as_bytes()[offset..offset + 4].to_vec();
+
+	let header = at(&dex, 8);
+	assert!(matches!(
+		dex.recompute_checksum_after_edit(8, &header, &[0; 4]),
+		Err(DexError::OffsetOutOfRange { start: 8, .. })
+	));
+	let tail = dex.as_bytes()[len - 2..].to_vec();
+	assert!(matches!(
+		dex.recompute_checksum_after_edit(len - 2, &[tail[0], tail[1], 0], &[0; 3]),
+		Err(DexError::OffsetOutOfRange { .. })
+	));
+	assert!(matches!(
+		dex.recompute_checksum_after_edit(0x70, &[0xff; 4], &[0; 4]),
+		Err(DexError::EditMismatch { offset: 0x70 })
+	));
+	let current = at(&dex, 0x70);
+	assert!(matches!(
+		dex.recompute_checksum_after_edit(0x70, &current, &[0; 3]),
+		Err(DexError::EditMismatch { offset: 0x70 })
+	));
+	assert_eq!(dex, original);
+}
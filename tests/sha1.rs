@@ -0,0 +1,48 @@
+//! FIPS 180 known answers for the SHA-1 implementation behind the DEX signature.
+
+use dex_checksum_tools::hex;
+use dex_checksum_tools::primitives::sha1;
+use dex_checksum_tools::primitives::Sha1;
+
+fn sha1_hex(data: &[u8]) -> String {
+	hex::encode(&sha1(data))
+}
+
+#[test]
+fn fips_180_known_answers() {
+	assert_eq!(sha1_hex(b""), "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+	assert_eq!(sha1_hex(b"abc"), "a9993e364706816aba3e25717850c26c9cd0d89d");
+	assert_eq!(
+		sha1_hex(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+		"84983e441c3bd26ebaae4aa1f95129e5e54670f1"
+	);
+	assert_eq!(
+		sha1_hex(&vec![b'a'; 1_000_000]),
+		"34aa973cd4c4daa4f61eeb2bdbad27316534016f"
+	);
+}
+
+#[test]
+fn padding_boundaries() {
+	// 55 bytes leave just enough room for the padding in one block, 56 need a second one.
+	let cases = [
+		(55, "c1c8bbdc22796e28c0e15163d20899b65621d65a"),
+		(56, "c2db330f6083854c99d4b5bfb6e8f29f201be699"),
+		(63, "03f09f5b158a7a8cdad920bddc29b81c18a551f5"),
+		(64, "0098ba824b5c16427bd7a1122a5a442a25ec644d"),
+		(65, "11655326c708d70319be2610e8a57d9a5b959d3b"),
+	];
+	for (len, digest) in cases {
+		assert_eq!(sha1_hex(&vec![b'a'; len]), digest, "{} bytes", len);
+	}
+}
+
+#[test]
+fn incremental_updates_match_one_call() {
+	let data: Vec<u8> = (0..1000u32).map(|i| (i * 31) as u8).collect();
+	for chunk in [1, 7, 55, 56, 63, 64, 65, 999] {
+		let mut hasher = Sha1::new();
+		data.chunks(chunk).for_each(|piece| hasher.update(piece));
+		assert_eq!(hasher.finalize(), sha1(&data), "chunks of {}", chunk);
+	}
+}
@@ -0,0 +1,40 @@
+//! FIPS 180-4 known answers for the SHA-256 behind `Dex::content_hash_sha256`.
+#![cfg(feature = "sha2")]
+
+use dex_checksum_tools::hex;
+use dex_checksum_tools::primitives::sha256;
+use dex_checksum_tools::primitives::Sha256;
+
+fn sha256_hex(data: &[u8]) -> String {
+	hex::encode(&sha256(data))
+}
+
+#[test]
+fn fips_180_4_known_answers() {
+	assert_eq!(
+		sha256_hex(b""),
+		"e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+	);
+	assert_eq!(
+		sha256_hex(b"abc"),
+		"ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+	);
+	assert_eq!(
+		sha256_hex(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+		"248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+	);
+	assert_eq!(
+		sha256_hex(&vec![b'a'; 1_000_000]),
+		"cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0"
+	);
+}
+
+#[test]
+fn incremental_updates_match_one_call() {
+	let data: Vec<u8> = (0..1000u32).map(|i| (i * 31) as u8).collect();
+	for chunk in [1, 7, 55, 56, 63, 64, 65, 999] {
+		let mut hasher = Sha256::new();
+		data.chunks(chunk).for_each(|piece| hasher.update(piece));
+		assert_eq!(hasher.finalize(), sha256(&data), "chunks of {}", chunk);
+	}
+}
@@ -0,0 +1,136 @@
+//! Known answers and round trips for the CRC-32, DEFLATE and ZIP code behind [`Apk`].
+
+use dex_checksum_tools::apk::Apk;
+use dex_checksum_tools::primitives::crc32;
+use dex_checksum_tools::primitives::deflate;
+use dex_checksum_tools::primitives::inflate;
+use dex_checksum_tools::primitives::inflate_limited;
+
+/// See `tests/apk.rs`. Its manifest is deflated with dynamic Huffman codes by zlib, and
+/// `assets/stored.bin` (`0..=255` repeated 80 times) into stored blocks.
+const SAMPLE_APK: &[u8] = include_bytes!("fixtures/sample.apk");
+
+/// Fills a buffer with reproducible pseudo-random bytes.
+fn pseudo_random(len: usize, seed: u64) -> Vec<u8> {
+	let mut state = seed | 1;
+	(0..len)
+		.map(|_| {
+			state ^= state << 13;
+			state ^= state >> 7;
+			state ^= state << 17;
+			state as u8
+		})
+		.collect()
+}
+
+/// Finds the local header of the entry called `name`, returning its CRC-32 and compressed data.
+fn entry_data<'a>(bytes: &'a [u8], name: &str) -> (u32, &'a [u8]) {
+	let u32_at = |offset: usize| u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+	let u16_at =
+		|offset: usize| u16::from_le_bytes(bytes[offset..offset + 2].try_into().unwrap()) as usize;
+	let mut offset = 0;
+	loop {
+		let (name_len, extra_len) = (u16_at(offset + 26), u16_at(offset + 28));
+		let data = offset + 30 + name_len + extra_len;
+		let end = data + u32_at(offset + 18) as usize;
+		if &bytes[offset + 30..offset + 30 + name_len] == name.as_bytes() {
+			return (u32_at(offset + 14), &bytes[data..end]);
+		}
+		offset = end;
+	}
+}
+
+#[test]
+fn crc32_known_answers() {
+	assert_eq!(crc32(b""), 0);
+	assert_eq!(crc32(b"123456789"), 0xcbf43926);
+	assert_eq!(
+		crc32(b"The quick brown fox jumps over the lazy dog"),
+		0x414fa339
+	);
+}
+
+#[test]
+fn inflate_undoes_deflate() {
+	let mut inputs = vec![
+		Vec::new(),
+		vec![0; 1],
+		vec![0; 100_000],
+		b"abcabcabcabcabc".repeat(1000),
+	];
+	inputs.extend(
+		[1, 2, 3, 257, 258, 259, 65_535, 65_536, 200_000].map(|len| pseudo_random(len, len as u64)),
+	);
+	for input in inputs {
+		let compressed = deflate(&input);
+		assert_eq!(
+			inflate(&compressed).unwrap(),
+			input,
+			"length {}",
+			input.len()
+		);
+	}
+}
+
+#[test]
+fn inflates_dynamic_and_stored_blocks_written_by_zlib() {
+	let (crc, manifest) = entry_data(SAMPLE_APK, "AndroidManifest.xml");
+	assert_eq!(
+		manifest[0] >> 1 & 3,
+		2,
+		"the first block uses dynamic Huffman codes"
+	);
+	let contents = inflate(manifest).unwrap();
+	assert!(contents.starts_with(b"<manifest package=\"com.example\">"));
+	assert_eq!(crc32(&contents), crc);
+
+	let (crc, stored) = entry_data(SAMPLE_APK, "assets/stored.bin");
+	assert_eq!(stored[0] >> 1 & 3, 0, "the first block is stored");
+	let contents = inflate(stored).unwrap();
+	assert_eq!(contents, (0..=255).collect::<Vec<u8>>().repeat(80));
+	assert_eq!(crc32(&contents), crc);
+}
+
+#[test]
+fn inflate_limited_stops_at_the_limit() {
+	let zeros = vec![0; 100_000];
+	let random = pseudo_random(100_000, 3);
+	// Long matches, literals and stored blocks each hit the limit.
+	for input in [zeros, random, b"abc".repeat(30_000)] {
+		let compressed = deflate(&input);
+		assert_eq!(inflate_limited(&compressed, input.len()).unwrap(), input);
+		assert!(inflate_limited(&compressed, input.len() - 1).is_err());
+		assert!(inflate_limited(&compressed, 10).is_err());
+	}
+}
+
+#[test]
+fn inflate_rejects_malformed_streams() {
+	let compressed = deflate(&pseudo_random(1000, 7));
+	assert!(inflate(&compressed[..compressed.len() / 2]).is_err());
+	// A block type of 3 is reserved.
+	assert!(inflate(&[0b111]).is_err());
+	// A stored block whose length is not followed by its complement.
+	assert!(inflate(&[0b001, 4, 0, 4, 0]).is_err());
+}
+
+#[test]
+fn zip_read_write_read_round_trip() {
+	let mut apk = Apk::from_bytes(SAMPLE_APK).unwrap();
+	apk.correct_all();
+	let mut written = Vec::new();
+	apk.write_to(&mut written).unwrap();
+
+	let reread = Apk::from_bytes(&written).unwrap();
+	assert_eq!(reread.dex_names(), apk.dex_names());
+	for name in apk.dex_names() {
+		let (dex, reread) = (
+			apk.dex(name).unwrap().unwrap(),
+			reread.dex(name).unwrap().unwrap(),
+		);
+		assert_eq!(dex, reread, "{}", name);
+	}
+	let mut rewritten = Vec::new();
+	reread.write_to(&mut rewritten).unwrap();
+	assert_eq!(rewritten, written);
+}